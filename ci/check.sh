@@ -3,7 +3,9 @@
 set -ex
 
 cargo fmt --all -- --check
-cargo clippy --all --tests -- \
-  -D clippy::all \
-  -D clippy::dbg_macro \
-  -D warnings
+for features in "" "--features codec" "--no-default-features"; do
+  cargo clippy --all --tests $features -- \
+    -D clippy::all \
+    -D clippy::dbg_macro \
+    -D warnings
+done
@@ -3,4 +3,5 @@
 set -ex
 
 cargo build --release
-cargo test --quiet
+cargo test --quiet --features codec
+cargo test --quiet --no-default-features
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use respite::{parse_frame, RespConfig};
+
+fuzz_target!(|input: &[u8]| {
+    let config = RespConfig::default();
+    if let Ok((_, consumed)) = parse_frame(input, &config) {
+        assert!(consumed > 0);
+        assert!(consumed <= input.len());
+    }
+});
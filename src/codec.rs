@@ -0,0 +1,224 @@
+use crate::{
+    parser::FrameParser, reader::READ_RESERVE_LIMIT, writer::encode_frame, RespConfig, RespError,
+    RespFrame, RespVersion,
+};
+use bytes::BytesMut;
+use std::cmp;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// A [`Decoder`] for reading [`RespFrame`]s with [`tokio_util::codec`].
+///
+/// Partial frames are left in the buffer until the rest of the frame arrives, and parsing resumes
+/// where it left off.
+#[derive(Debug)]
+pub struct RespDecoder {
+    /// Reader config.
+    config: RespConfig,
+
+    /// Progress through the frame at the start of the buffer.
+    parser: FrameParser,
+
+    /// The current version, enforced when [`RespConfig::strict_version`] is enabled.
+    version: RespVersion,
+}
+
+impl RespDecoder {
+    /// Create a new [`RespDecoder`] from a [`RespConfig`].
+    pub fn new(config: RespConfig) -> Self {
+        Self {
+            config,
+            parser: FrameParser::default(),
+            version: RespVersion::V2,
+        }
+    }
+
+    /// The current version.
+    pub fn version(&self) -> RespVersion {
+        self.version
+    }
+
+    /// Set the current version, enforced when [`RespConfig::strict_version`] is enabled.
+    ///
+    /// The default is [`RespVersion::V2`].
+    pub fn set_version(&mut self, version: RespVersion) {
+        self.version = version;
+    }
+}
+
+impl Default for RespDecoder {
+    fn default() -> Self {
+        Self::new(RespConfig::default())
+    }
+}
+
+impl Decoder for RespDecoder {
+    type Item = RespFrame;
+    type Error = RespError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<RespFrame>, RespError> {
+        let Some((frame, len)) = self.parser.parse(src, &self.config, self.version)? else {
+            src.reserve(cmp::min(self.parser.wanted(src), READ_RESERVE_LIMIT));
+            return Ok(None);
+        };
+        Ok(Some(frame.into_frame(&src.split_to(len).freeze())))
+    }
+}
+
+/// An [`Encoder`] for writing [`RespFrame`]s with [`tokio_util::codec`].
+#[derive(Debug)]
+pub struct RespEncoder {
+    /// The current version.
+    version: RespVersion,
+}
+
+impl RespEncoder {
+    /// Create a new [`RespEncoder`].
+    pub fn new() -> Self {
+        Self {
+            version: RespVersion::V2,
+        }
+    }
+
+    /// The current version.
+    pub fn version(&self) -> RespVersion {
+        self.version
+    }
+
+    /// Set the version to write.
+    ///
+    /// The default is [`RespVersion::V2`].
+    pub fn set_version(&mut self, version: RespVersion) {
+        self.version = version;
+    }
+}
+
+impl Default for RespEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Encoder<RespFrame> for RespEncoder {
+    type Error = RespError;
+
+    fn encode(&mut self, frame: RespFrame, dst: &mut BytesMut) -> Result<(), RespError> {
+        encode_frame(dst, frame.as_frame_ref(), self.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"*3\r\n$3\r\nSET\r\n+OK\r\n:-12\r\n%1\r\n#t\r\n,1.5\r\n=7\r\ntxt:abc\r\n";
+
+    fn frames() -> Vec<RespFrame> {
+        vec![
+            RespFrame::Array(3),
+            RespFrame::BlobString("SET".into()),
+            RespFrame::SimpleString("OK".into()),
+            RespFrame::Integer(-12),
+            RespFrame::Map(1),
+            RespFrame::Boolean(true),
+            RespFrame::Double(1.5.into()),
+            RespFrame::Verbatim("txt".into(), "abc".into()),
+        ]
+    }
+
+    #[test]
+    fn decode_split() -> Result<(), RespError> {
+        for split in 0..=INPUT.len() {
+            let mut decoder = RespDecoder::default();
+            let mut src = BytesMut::from(&INPUT[..split]);
+            let mut decoded = Vec::new();
+            while let Some(frame) = decoder.decode(&mut src)? {
+                decoded.push(frame);
+            }
+            src.extend_from_slice(&INPUT[split..]);
+            while let Some(frame) = decoder.decode(&mut src)? {
+                decoded.push(frame);
+            }
+            assert_eq!(decoded, frames());
+            assert!(src.is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn decode_bytewise() -> Result<(), RespError> {
+        let mut decoder = RespDecoder::default();
+        let mut src = BytesMut::new();
+        let mut decoded = Vec::new();
+        for &byte in INPUT {
+            src.extend_from_slice(&[byte]);
+            while let Some(frame) = decoder.decode(&mut src)? {
+                decoded.push(frame);
+            }
+        }
+        assert_eq!(decoded, frames());
+        Ok(())
+    }
+
+    #[test]
+    fn decode_error() {
+        let mut decoder = RespDecoder::default();
+        let mut src = BytesMut::from(&b"@x\r\n"[..]);
+        assert!(matches!(
+            decoder.decode(&mut src),
+            Err(RespError::UnknownType(b'@'))
+        ));
+    }
+
+    #[test]
+    fn encode() -> Result<(), RespError> {
+        let mut encoder = RespEncoder::new();
+        assert_eq!(encoder.version(), RespVersion::V2);
+        encoder.set_version(RespVersion::V3);
+        assert_eq!(encoder.version(), RespVersion::V3);
+        let mut dst = BytesMut::new();
+        for frame in frames() {
+            encoder.encode(frame, &mut dst)?;
+        }
+        assert_eq!(&dst[..], INPUT);
+
+        let mut encoder = RespEncoder::default();
+        assert!(matches!(
+            encoder.encode(RespFrame::Map(1), &mut dst),
+            Ok(())
+        ));
+        assert!(dst.ends_with(b"*2\r\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_resumes() -> Result<(), RespError> {
+        let mut decoder = RespDecoder::default();
+        let mut src = BytesMut::from(&b"$10\r\n01234"[..]);
+        assert_eq!(decoder.decode(&mut src)?, None);
+        assert!(src.capacity() >= 12);
+
+        src.extend_from_slice(b"56789\r\n+OK");
+        assert_eq!(
+            decoder.decode(&mut src)?,
+            Some(RespFrame::BlobString("0123456789".into()))
+        );
+        assert_eq!(decoder.decode(&mut src)?, None);
+        assert_eq!(&src[..], b"+OK");
+        Ok(())
+    }
+
+    #[test]
+    fn encode_into_dst() -> Result<(), RespError> {
+        let mut encoder = RespEncoder::new();
+        let mut dst = BytesMut::from(&b"+OK\r\n"[..]);
+        encoder.encode(RespFrame::BlobString("hi!".into()), &mut dst)?;
+        assert_eq!(&dst[..], b"+OK\r\n$3\r\nhi!\r\n");
+
+        assert!(matches!(
+            encoder.encode(RespFrame::SimpleString("a\r\nb".into()), &mut dst),
+            Err(RespError::Newline)
+        ));
+        assert_eq!(&dst[..], b"+OK\r\n$3\r\nhi!\r\n");
+        Ok(())
+    }
+}
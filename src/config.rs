@@ -1,4 +1,4 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use triomphe::Arc;
 
 /// Configuration of limits for reading a RESP stream.
@@ -8,8 +8,53 @@ pub struct RespConfig {
     /// The maximum blob frame size.
     blob_limit: Arc<AtomicUsize>,
 
+    /// The maximum number of digits in a bignum.
+    bignum_limit: Arc<AtomicUsize>,
+
     /// The maximum inline request size.
     inline_limit: Arc<AtomicUsize>,
+
+    /// The maximum number of elements in one value, across all nested aggregates.
+    total_elements_limit: Arc<AtomicUsize>,
+
+    /// The maximum number of payload bytes in one value, across all nested aggregates.
+    total_limit: Arc<AtomicUsize>,
+
+    /// The maximum number of inline arguments produced before yielding to other tasks.
+    inline_poll_limit: Arc<AtomicUsize>,
+
+    /// Whether inline requests are accepted.
+    inline_enabled: Arc<AtomicBool>,
+
+    /// Whether every request is read as an inline request.
+    inline_only: Arc<AtomicBool>,
+
+    /// Whether to skip an attribute preceding a request.
+    skip_request_attributes: Arc<AtomicBool>,
+
+    /// Whether to report the declared size of multibulk arguments.
+    sized_arguments: Arc<AtomicBool>,
+
+    /// Whether to report the declared number of multibulk arguments.
+    multibulk_counts: Arc<AtomicBool>,
+
+    /// Whether to reject frames that aren't valid in the reader's version.
+    strict_version: Arc<AtomicBool>,
+
+    /// Whether to accept uppercase booleans.
+    lenient_booleans: Arc<AtomicBool>,
+
+    /// Whether to accept a bare newline as a line terminator.
+    lenient_newlines: Arc<AtomicBool>,
+
+    /// Whether an error nested in an aggregate fails the whole value.
+    fail_on_nested_error: Arc<AtomicBool>,
+
+    /// Whether to read frames of unknown types as lines instead of failing.
+    unknown_types: Arc<AtomicBool>,
+
+    /// The buffered input size beyond which no more input is read until it's needed.
+    read_high_water: Arc<AtomicUsize>,
 }
 
 impl Default for RespConfig {
@@ -17,11 +62,38 @@ impl Default for RespConfig {
         Self {
             inline_limit: Arc::new(AtomicUsize::new(1024 * 64)),
             blob_limit: Arc::new(AtomicUsize::new(512 * 1024 * 1024)),
+            bignum_limit: Arc::new(AtomicUsize::new(usize::MAX)),
+            total_elements_limit: Arc::new(AtomicUsize::new(usize::MAX)),
+            total_limit: Arc::new(AtomicUsize::new(usize::MAX)),
+            inline_poll_limit: Arc::new(AtomicUsize::new(usize::MAX)),
+            inline_enabled: Arc::new(AtomicBool::new(true)),
+            inline_only: Arc::new(AtomicBool::new(false)),
+            skip_request_attributes: Arc::new(AtomicBool::new(false)),
+            sized_arguments: Arc::new(AtomicBool::new(false)),
+            multibulk_counts: Arc::new(AtomicBool::new(false)),
+            strict_version: Arc::new(AtomicBool::new(false)),
+            lenient_booleans: Arc::new(AtomicBool::new(false)),
+            lenient_newlines: Arc::new(AtomicBool::new(false)),
+            fail_on_nested_error: Arc::new(AtomicBool::new(false)),
+            unknown_types: Arc::new(AtomicBool::new(false)),
+            read_high_water: Arc::new(AtomicUsize::new(usize::MAX)),
         }
     }
 }
 
 impl RespConfig {
+    /// Create a [`RespConfigBuilder`], starting from the default config.
+    ///
+    /// ```
+    /// # use respite::RespConfig;
+    /// let config = RespConfig::builder().blob_limit(1024).inline_limit(64).build();
+    /// assert_eq!(config.blob_limit(), 1024);
+    /// assert_eq!(config.inline_limit(), 64);
+    /// ```
+    pub fn builder() -> RespConfigBuilder {
+        RespConfigBuilder::default()
+    }
+
     /// Get the blog frame size limit.
     pub fn blob_limit(&self) -> usize {
         self.blob_limit.load(Ordering::Relaxed)
@@ -32,6 +104,16 @@ impl RespConfig {
         self.blob_limit.store(value, Ordering::Relaxed)
     }
 
+    /// Get the bignum digit limit, which is unlimited by default.
+    pub fn bignum_limit(&self) -> usize {
+        self.bignum_limit.load(Ordering::Relaxed)
+    }
+
+    /// Set the bignum digit limit.
+    pub fn set_bignum_limit(&mut self, value: usize) {
+        self.bignum_limit.store(value, Ordering::Relaxed)
+    }
+
     /// Get the inline request size limit.
     pub fn inline_limit(&self) -> usize {
         self.inline_limit.load(Ordering::Relaxed)
@@ -41,4 +123,369 @@ impl RespConfig {
     pub fn set_inline_limit(&mut self, value: usize) {
         self.inline_limit.store(value, Ordering::Relaxed)
     }
+
+    /// Get the total elements limit, which is unlimited by default.
+    ///
+    /// Each entry of a map or attribute counts as two elements, a key and a value.
+    pub fn total_elements_limit(&self) -> usize {
+        self.total_elements_limit.load(Ordering::Relaxed)
+    }
+
+    /// Set the total elements limit.
+    pub fn set_total_elements_limit(&mut self, value: usize) {
+        self.total_elements_limit.store(value, Ordering::Relaxed)
+    }
+
+    /// Get the total payload limit, which is unlimited by default.
+    ///
+    /// This caps the bytes of strings, errors, and bignums buffered while reading one value with
+    /// [`RespReader::value`].
+    ///
+    /// [`RespReader::value`]: crate::RespReader::value
+    pub fn total_limit(&self) -> usize {
+        self.total_limit.load(Ordering::Relaxed)
+    }
+
+    /// Set the total payload limit.
+    pub fn set_total_limit(&mut self, value: usize) {
+        self.total_limit.store(value, Ordering::Relaxed)
+    }
+
+    /// Get the inline poll limit, which is unlimited by default.
+    ///
+    /// After producing this many arguments from one inline request, the reader yields so that a
+    /// huge inline request doesn't monopolize its task.
+    pub fn inline_poll_limit(&self) -> usize {
+        self.inline_poll_limit.load(Ordering::Relaxed)
+    }
+
+    /// Set the inline poll limit.
+    pub fn set_inline_poll_limit(&mut self, value: usize) {
+        self.inline_poll_limit.store(value, Ordering::Relaxed)
+    }
+
+    /// Are inline requests accepted?
+    pub fn inline_enabled(&self) -> bool {
+        self.inline_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Set whether inline requests are accepted.
+    pub fn set_inline_enabled(&mut self, value: bool) {
+        self.inline_enabled.store(value, Ordering::Relaxed)
+    }
+
+    /// Is every request read as an inline request?
+    ///
+    /// This is meant for telnet-style servers. Lines are never parsed as frames, even if they
+    /// start with `*`, and [`RespConfig::inline_enabled`] is ignored.
+    pub fn inline_only(&self) -> bool {
+        self.inline_only.load(Ordering::Relaxed)
+    }
+
+    /// Set whether every request is read as an inline request.
+    pub fn set_inline_only(&mut self, value: bool) {
+        self.inline_only.store(value, Ordering::Relaxed)
+    }
+
+    /// Are attributes preceding a request skipped?
+    pub fn skip_request_attributes(&self) -> bool {
+        self.skip_request_attributes.load(Ordering::Relaxed)
+    }
+
+    /// Set whether attributes preceding a request are skipped.
+    pub fn set_skip_request_attributes(&mut self, value: bool) {
+        self.skip_request_attributes.store(value, Ordering::Relaxed)
+    }
+
+    /// Are multibulk arguments reported as [`RespRequest::SizedArgument`]?
+    ///
+    /// [`RespRequest::SizedArgument`]: crate::RespRequest::SizedArgument
+    pub fn sized_arguments(&self) -> bool {
+        self.sized_arguments.load(Ordering::Relaxed)
+    }
+
+    /// Set whether multibulk arguments are reported as [`RespRequest::SizedArgument`].
+    ///
+    /// [`RespRequest::SizedArgument`]: crate::RespRequest::SizedArgument
+    pub fn set_sized_arguments(&mut self, value: bool) {
+        self.sized_arguments.store(value, Ordering::Relaxed)
+    }
+
+    /// Is the argument count of each multibulk request reported as [`RespRequest::Multibulk`]?
+    ///
+    /// [`RespRequest::Multibulk`]: crate::RespRequest::Multibulk
+    pub fn multibulk_counts(&self) -> bool {
+        self.multibulk_counts.load(Ordering::Relaxed)
+    }
+
+    /// Set whether the argument count of each multibulk request is reported as
+    /// [`RespRequest::Multibulk`].
+    ///
+    /// [`RespRequest::Multibulk`]: crate::RespRequest::Multibulk
+    pub fn set_multibulk_counts(&mut self, value: bool) {
+        self.multibulk_counts.store(value, Ordering::Relaxed)
+    }
+
+    /// Are frames that aren't valid in the reader's version rejected?
+    pub fn strict_version(&self) -> bool {
+        self.strict_version.load(Ordering::Relaxed)
+    }
+
+    /// Set whether frames that aren't valid in the reader's version are rejected.
+    pub fn set_strict_version(&mut self, value: bool) {
+        self.strict_version.store(value, Ordering::Relaxed)
+    }
+
+    /// Are uppercase booleans (`#T` and `#F`) accepted?
+    pub fn lenient_booleans(&self) -> bool {
+        self.lenient_booleans.load(Ordering::Relaxed)
+    }
+
+    /// Set whether uppercase booleans (`#T` and `#F`) are accepted.
+    pub fn set_lenient_booleans(&mut self, value: bool) {
+        self.lenient_booleans.store(value, Ordering::Relaxed)
+    }
+
+    /// Is a bare `\n` accepted as a line terminator?
+    pub fn lenient_newlines(&self) -> bool {
+        self.lenient_newlines.load(Ordering::Relaxed)
+    }
+
+    /// Set whether a bare `\n` is accepted as a line terminator, in addition to `\r\n`.
+    pub fn set_lenient_newlines(&mut self, value: bool) {
+        self.lenient_newlines.store(value, Ordering::Relaxed)
+    }
+
+    /// Does an error nested in an aggregate fail the whole value with [`RespError::Server`]?
+    ///
+    /// [`RespError::Server`]: crate::RespError::Server
+    pub fn fail_on_nested_error(&self) -> bool {
+        self.fail_on_nested_error.load(Ordering::Relaxed)
+    }
+
+    /// Set whether an error nested in an aggregate fails the whole value.
+    ///
+    /// The rest of the aggregate is left unread, so the stream can't be used afterward.
+    pub fn set_fail_on_nested_error(&mut self, value: bool) {
+        self.fail_on_nested_error.store(value, Ordering::Relaxed)
+    }
+
+    /// Are frames of unknown types read as [`RespFrame::Unknown`] instead of failing?
+    ///
+    /// [`RespFrame::Unknown`]: crate::RespFrame::Unknown
+    pub fn unknown_types(&self) -> bool {
+        self.unknown_types.load(Ordering::Relaxed)
+    }
+
+    /// Set whether frames of unknown types are read as [`RespFrame::Unknown`].
+    ///
+    /// [`RespFrame::Unknown`]: crate::RespFrame::Unknown
+    pub fn set_unknown_types(&mut self, value: bool) {
+        self.unknown_types.store(value, Ordering::Relaxed)
+    }
+
+    /// Get the read high water mark, which is unlimited by default.
+    ///
+    /// Once this many bytes are buffered, the reader stops reading from the inner stream until
+    /// it needs more input to finish a frame. This applies backpressure to pipelined input.
+    pub fn read_high_water(&self) -> usize {
+        self.read_high_water.load(Ordering::Relaxed)
+    }
+
+    /// Set the read high water mark.
+    pub fn set_read_high_water(&mut self, value: usize) {
+        self.read_high_water.store(value, Ordering::Relaxed)
+    }
+}
+
+/// A builder for [`RespConfig`].
+#[derive(Debug, Default)]
+pub struct RespConfigBuilder {
+    config: RespConfig,
+}
+
+impl RespConfigBuilder {
+    /// Set the blob frame size limit.
+    pub fn blob_limit(mut self, value: usize) -> Self {
+        self.config.set_blob_limit(value);
+        self
+    }
+
+    /// Set the bignum digit limit.
+    pub fn bignum_limit(mut self, value: usize) -> Self {
+        self.config.set_bignum_limit(value);
+        self
+    }
+
+    /// Set the inline request size limit.
+    pub fn inline_limit(mut self, value: usize) -> Self {
+        self.config.set_inline_limit(value);
+        self
+    }
+
+    /// Set the total elements limit.
+    pub fn total_elements_limit(mut self, value: usize) -> Self {
+        self.config.set_total_elements_limit(value);
+        self
+    }
+
+    /// Set the total payload limit.
+    pub fn total_limit(mut self, value: usize) -> Self {
+        self.config.set_total_limit(value);
+        self
+    }
+
+    /// Set the inline poll limit.
+    pub fn inline_poll_limit(mut self, value: usize) -> Self {
+        self.config.set_inline_poll_limit(value);
+        self
+    }
+
+    /// Set whether inline requests are accepted.
+    pub fn inline_enabled(mut self, value: bool) -> Self {
+        self.config.set_inline_enabled(value);
+        self
+    }
+
+    /// Set whether every request is read as an inline request.
+    pub fn inline_only(mut self, value: bool) -> Self {
+        self.config.set_inline_only(value);
+        self
+    }
+
+    /// Set whether attributes preceding a request are skipped.
+    pub fn skip_request_attributes(mut self, value: bool) -> Self {
+        self.config.set_skip_request_attributes(value);
+        self
+    }
+
+    /// Set whether multibulk arguments are reported with their declared size.
+    pub fn sized_arguments(mut self, value: bool) -> Self {
+        self.config.set_sized_arguments(value);
+        self
+    }
+
+    /// Set whether the argument count of each multibulk request is reported.
+    pub fn multibulk_counts(mut self, value: bool) -> Self {
+        self.config.set_multibulk_counts(value);
+        self
+    }
+
+    /// Set whether frames that aren't valid in the reader's version are rejected.
+    pub fn strict_version(mut self, value: bool) -> Self {
+        self.config.set_strict_version(value);
+        self
+    }
+
+    /// Set whether uppercase booleans are accepted.
+    pub fn lenient_booleans(mut self, value: bool) -> Self {
+        self.config.set_lenient_booleans(value);
+        self
+    }
+
+    /// Set whether a bare newline is accepted as a line terminator.
+    pub fn lenient_newlines(mut self, value: bool) -> Self {
+        self.config.set_lenient_newlines(value);
+        self
+    }
+
+    /// Set whether an error nested in an aggregate fails the whole value.
+    pub fn fail_on_nested_error(mut self, value: bool) -> Self {
+        self.config.set_fail_on_nested_error(value);
+        self
+    }
+
+    /// Set whether frames of unknown types are read as lines.
+    pub fn unknown_types(mut self, value: bool) -> Self {
+        self.config.set_unknown_types(value);
+        self
+    }
+
+    /// Set the read high water mark.
+    pub fn read_high_water(mut self, value: usize) -> Self {
+        self.config.set_read_high_water(value);
+        self
+    }
+
+    /// Finish building the [`RespConfig`].
+    pub fn build(self) -> RespConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder() {
+        let config = RespConfig::builder()
+            .blob_limit(5)
+            .bignum_limit(6)
+            .inline_limit(7)
+            .total_elements_limit(9)
+            .total_limit(10)
+            .inline_poll_limit(12)
+            .inline_enabled(false)
+            .inline_only(true)
+            .skip_request_attributes(true)
+            .sized_arguments(true)
+            .multibulk_counts(true)
+            .strict_version(true)
+            .lenient_booleans(true)
+            .lenient_newlines(true)
+            .fail_on_nested_error(true)
+            .unknown_types(true)
+            .read_high_water(11)
+            .build();
+        assert_eq!(config.blob_limit(), 5);
+        assert_eq!(config.bignum_limit(), 6);
+        assert_eq!(config.inline_limit(), 7);
+        assert_eq!(config.total_elements_limit(), 9);
+        assert_eq!(config.total_limit(), 10);
+        assert_eq!(config.inline_poll_limit(), 12);
+        assert!(!config.inline_enabled());
+        assert!(config.inline_only());
+        assert!(config.skip_request_attributes());
+        assert!(config.sized_arguments());
+        assert!(config.multibulk_counts());
+        assert!(config.strict_version());
+        assert!(config.lenient_booleans());
+        assert!(config.lenient_newlines());
+        assert!(config.fail_on_nested_error());
+        assert!(config.unknown_types());
+        assert_eq!(config.read_high_water(), 11);
+    }
+
+    #[test]
+    fn builder_defaults() {
+        let config = RespConfig::builder().build();
+        let default = RespConfig::default();
+        assert_eq!(config.blob_limit(), default.blob_limit());
+        assert_eq!(config.bignum_limit(), default.bignum_limit());
+        assert_eq!(config.inline_limit(), default.inline_limit());
+        assert_eq!(
+            config.total_elements_limit(),
+            default.total_elements_limit()
+        );
+        assert_eq!(config.total_limit(), default.total_limit());
+        assert_eq!(config.inline_poll_limit(), default.inline_poll_limit());
+        assert_eq!(config.inline_enabled(), default.inline_enabled());
+        assert_eq!(config.inline_only(), default.inline_only());
+        assert_eq!(
+            config.skip_request_attributes(),
+            default.skip_request_attributes()
+        );
+        assert_eq!(config.sized_arguments(), default.sized_arguments());
+        assert_eq!(config.multibulk_counts(), default.multibulk_counts());
+        assert_eq!(config.strict_version(), default.strict_version());
+        assert_eq!(config.lenient_booleans(), default.lenient_booleans());
+        assert_eq!(config.lenient_newlines(), default.lenient_newlines());
+        assert_eq!(
+            config.fail_on_nested_error(),
+            default.fail_on_nested_error()
+        );
+        assert_eq!(config.unknown_types(), default.unknown_types());
+        assert_eq!(config.read_high_water(), default.read_high_water());
+    }
 }
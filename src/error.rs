@@ -1,70 +1,282 @@
-use thiserror::Error;
+use alloc::string::String;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// A broad category of [`RespError`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum RespErrorKind {
+    /// The stream ended, possibly in the middle of a frame.
+    Eof,
+
+    /// The underlying stream failed.
+    Io,
+
+    /// A configured limit was exceeded.
+    Limit,
+
+    /// The stream contained an error reply.
+    Server,
+
+    /// The stream is malformed or unexpected.
+    Protocol,
+}
 
 /// An error encountered while reading a RESP stream.
-#[derive(Debug, Error)]
+#[derive(Debug)]
 #[non_exhaustive]
 pub enum RespError {
     /// Reached the end of the stream unexpectedly
-    #[error("unexpected end of input")]
     EndOfInput,
 
     /// Received an invalid boolean
-    #[error("invalid boolean")]
     InvalidBoolean,
 
     /// Received an invalid blob
-    #[error("invalid blob length")]
     InvalidBlobLength,
 
+    /// Received an invalid aggregate length
+    InvalidLength,
+
     /// Received an invalid double
-    #[error("invalid double")]
     InvalidDouble,
 
     /// Received an invalid integer
-    #[error("invalid integer")]
     InvalidInteger,
 
     /// Received an invalid map
-    #[error("invalid map")]
     InvalidMap,
 
     /// Received an invalid set
-    #[error("invalid set")]
     InvalidSet,
 
     /// Received an invalid verbatim
-    #[error("invalid verbatim")]
     InvalidVerbatim,
 
     /// Error reading from the stream.
-    #[error("io error")]
-    IO(#[from] std::io::Error),
+    #[cfg(feature = "std")]
+    IO(io::Error),
 
     /// Simple frame cannot contain a newline.
-    #[error("newline is not allowed in this frame")]
     Newline,
 
+    /// Received an unknown protocol version
+    InvalidVersion,
+
     /// Unsupported in current version.
-    #[error("unsupported in the current version")]
     Version,
 
     /// Expected a primitive, but got a complex value
-    #[error("map keys and set values must be primitives")]
     RespPrimitive,
 
     /// Received an inline request that was too big.
-    #[error("too big inline request")]
     TooBigInline,
 
+    /// Received a bignum with too many digits.
+    TooBigBignum,
+
+    /// Received a blob larger than the blob limit, or with too many digits in its size.
+    TooBigBlob,
+
+    /// A value was nested too deeply
+    DepthLimit,
+
+    /// Received a value with too many elements in total.
+    TooManyElements,
+
+    /// Received a value with too many bytes in total.
+    TooLarge,
+
     /// Unexpected byte sequence
-    #[error("expected {:?}, got {:?}", char::from(*.0), char::from(*.1))]
     Unexpected(u8, u8),
 
     /// Unknown RESP type
-    #[error("unknown resp type: {:?}", char::from(*.0))]
     UnknownType(u8),
 
+    /// Expected an array, but got another frame
+    ExpectedArray,
+
+    /// Expected a map, but got another value
+    ExpectedMap,
+
+    /// Expected a scalar, but got an aggregate frame
+    ExpectedScalar,
+
+    /// Expected a string, but got another frame
+    ExpectedString,
+
+    /// Received an array with the wrong number of elements
+    UnexpectedArity { expected: usize, got: usize },
+
+    /// Received a reply with an unexpected shape
+    InvalidReply,
+
+    /// Wrote a different number of frames than an aggregate header declared
+    FrameCount,
+
+    /// Expected the end of the stream, but there was more data
+    TrailingData,
+
+    /// Invalid argument in an inline command
+    InvalidArgument,
+
     /// Invalid inline command
-    #[error("invalid inline command")]
     InvalidInline,
+
+    /// Received an error nested in an aggregate
+    Server(String),
+}
+
+impl fmt::Display for RespError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RespError::*;
+        match self {
+            EndOfInput => f.write_str("unexpected end of input"),
+            InvalidBoolean => f.write_str("invalid boolean"),
+            InvalidBlobLength => f.write_str("invalid blob length"),
+            InvalidLength => f.write_str("invalid length"),
+            InvalidDouble => f.write_str("invalid double"),
+            InvalidInteger => f.write_str("invalid integer"),
+            InvalidMap => f.write_str("invalid map"),
+            InvalidSet => f.write_str("invalid set"),
+            InvalidVerbatim => f.write_str("invalid verbatim"),
+            #[cfg(feature = "std")]
+            IO(_) => f.write_str("io error"),
+            Newline => f.write_str("newline is not allowed in this frame"),
+            InvalidVersion => f.write_str("invalid protocol version"),
+            Version => f.write_str("unsupported in the current version"),
+            RespPrimitive => f.write_str("map keys and set values must be primitives"),
+            TooBigInline => f.write_str("too big inline request"),
+            TooBigBignum => f.write_str("too big bignum"),
+            TooBigBlob => f.write_str("too big blob"),
+            DepthLimit => f.write_str("depth limit exceeded"),
+            TooManyElements => f.write_str("too many elements"),
+            TooLarge => f.write_str("value too large"),
+            Unexpected(expected, got) => write!(
+                f,
+                "expected {:?}, got {:?}",
+                char::from(*expected),
+                char::from(*got)
+            ),
+            UnknownType(kind) => write!(f, "unknown resp type: {:?}", char::from(*kind)),
+            ExpectedArray => f.write_str("expected an array"),
+            ExpectedMap => f.write_str("expected a map"),
+            ExpectedScalar => f.write_str("expected a scalar"),
+            ExpectedString => f.write_str("expected a string"),
+            UnexpectedArity { expected, got } => {
+                write!(f, "expected {expected} elements, got {got}")
+            }
+            InvalidReply => f.write_str("invalid reply"),
+            FrameCount => f.write_str("aggregate frame count mismatch"),
+            TrailingData => f.write_str("unexpected trailing data"),
+            InvalidArgument => f.write_str("invalid argument"),
+            InvalidInline => f.write_str("invalid inline command"),
+            Server(message) => write!(f, "server error: {message}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RespError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RespError::IO(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for RespError {
+    fn from(error: io::Error) -> Self {
+        RespError::IO(error)
+    }
+}
+
+impl RespError {
+    /// Did the stream end unexpectedly?
+    pub fn is_eof(&self) -> bool {
+        self.kind() == RespErrorKind::Eof
+    }
+
+    /// Classify the error into a broad [`RespErrorKind`].
+    pub fn kind(&self) -> RespErrorKind {
+        use RespError::*;
+        match self {
+            EndOfInput => RespErrorKind::Eof,
+            #[cfg(feature = "std")]
+            IO(error) if error.kind() == io::ErrorKind::UnexpectedEof => RespErrorKind::Eof,
+            #[cfg(feature = "std")]
+            IO(_) => RespErrorKind::Io,
+            TooBigInline | TooBigBignum | TooBigBlob | DepthLimit | TooManyElements | TooLarge => {
+                RespErrorKind::Limit
+            }
+            Server(_) => RespErrorKind::Server,
+            _ => RespErrorKind::Protocol,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind() {
+        use RespErrorKind::*;
+        assert_eq!(RespError::EndOfInput.kind(), Eof);
+        assert_eq!(RespError::TooBigInline.kind(), Limit);
+        assert_eq!(RespError::TooManyElements.kind(), Limit);
+        assert_eq!(RespError::DepthLimit.kind(), Limit);
+        assert_eq!(RespError::TooBigBlob.kind(), Limit);
+        assert_eq!(RespError::InvalidBlobLength.kind(), Protocol);
+        assert_eq!(RespError::Unexpected(b'\r', b'x').kind(), Protocol);
+        assert_eq!(RespError::UnknownType(b'x').kind(), Protocol);
+        assert_eq!(RespError::Server("ERR".into()).kind(), Server);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_kind() {
+        let eof = io::Error::from(io::ErrorKind::UnexpectedEof);
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert_eq!(RespError::IO(eof).kind(), RespErrorKind::Eof);
+        assert_eq!(RespError::IO(reset).kind(), RespErrorKind::Io);
+    }
+
+    #[test]
+    fn display() {
+        use alloc::string::ToString;
+        assert_eq!(RespError::InvalidMap.to_string(), "invalid map");
+        assert_eq!(
+            RespError::Unexpected(b'\r', b'x').to_string(),
+            "expected '\\r', got 'x'"
+        );
+        assert_eq!(
+            RespError::UnexpectedArity {
+                expected: 2,
+                got: 3
+            }
+            .to_string(),
+            "expected 2 elements, got 3"
+        );
+        assert_eq!(
+            RespError::Server("ERR".into()).to_string(),
+            "server error: ERR"
+        );
+    }
+
+    #[test]
+    fn is_eof() {
+        assert!(RespError::EndOfInput.is_eof());
+        assert!(!RespError::InvalidInteger.is_eof());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_is_eof() {
+        assert!(RespError::IO(io::ErrorKind::UnexpectedEof.into()).is_eof());
+        assert!(!RespError::IO(io::ErrorKind::BrokenPipe.into()).is_eof());
+    }
 }
@@ -2,7 +2,7 @@ use bytes::Bytes;
 use ordered_float::OrderedFloat;
 
 /// A single frame in a RESP stream.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum RespFrame {
     Array(usize),
     Attribute(usize),
@@ -18,5 +18,164 @@ pub enum RespFrame {
     Set(usize),
     SimpleError(Bytes),
     SimpleString(Bytes),
+    /// A frame of an unknown type, read as a line when [`RespConfig::unknown_types`] is enabled.
+    ///
+    /// [`RespConfig::unknown_types`]: crate::RespConfig::unknown_types
+    Unknown(u8, Bytes),
     Verbatim(Bytes, Bytes),
 }
+
+/// A single frame in a RESP stream, borrowing its data.
+///
+/// See [`RespReader::frame_ref`].
+///
+/// [`RespReader::frame_ref`]: crate::RespReader::frame_ref
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RespFrameRef<'a> {
+    Array(usize),
+    Attribute(usize),
+    Bignum(&'a [u8]),
+    BlobError(&'a [u8]),
+    BlobString(&'a [u8]),
+    Boolean(bool),
+    Double(OrderedFloat<f64>),
+    Integer(i64),
+    Map(usize),
+    Nil,
+    Push(usize),
+    Set(usize),
+    SimpleError(&'a [u8]),
+    SimpleString(&'a [u8]),
+    Unknown(u8, &'a [u8]),
+    Verbatim(&'a [u8], &'a [u8]),
+}
+
+impl RespFrame {
+    /// Borrow this frame as a [`RespFrameRef`].
+    pub fn as_frame_ref(&self) -> RespFrameRef<'_> {
+        use RespFrame::*;
+
+        match self {
+            Array(size) => RespFrameRef::Array(*size),
+            Attribute(size) => RespFrameRef::Attribute(*size),
+            Bignum(value) => RespFrameRef::Bignum(value),
+            BlobError(value) => RespFrameRef::BlobError(value),
+            BlobString(value) => RespFrameRef::BlobString(value),
+            Boolean(value) => RespFrameRef::Boolean(*value),
+            Double(value) => RespFrameRef::Double(*value),
+            Integer(value) => RespFrameRef::Integer(*value),
+            Map(size) => RespFrameRef::Map(*size),
+            Nil => RespFrameRef::Nil,
+            Push(size) => RespFrameRef::Push(*size),
+            Set(size) => RespFrameRef::Set(*size),
+            SimpleError(value) => RespFrameRef::SimpleError(value),
+            SimpleString(value) => RespFrameRef::SimpleString(value),
+            Unknown(kind, value) => RespFrameRef::Unknown(*kind, value),
+            Verbatim(format, value) => RespFrameRef::Verbatim(format, value),
+        }
+    }
+
+    /// The declared size of an aggregate frame, or `None` for any other frame.
+    ///
+    /// For maps and attributes this is the number of pairs, not the number of following frames.
+    pub fn aggregate_len(&self) -> Option<usize> {
+        use RespFrame::*;
+
+        match self {
+            Array(size) | Attribute(size) | Map(size) | Push(size) | Set(size) => Some(*size),
+            _ => None,
+        }
+    }
+
+    /// Is this an aggregate frame, followed by other frames?
+    pub fn is_aggregate(&self) -> bool {
+        self.aggregate_len().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates() {
+        use RespFrame::*;
+
+        let cases = [
+            (Array(2), Some(2)),
+            (Attribute(3), Some(3)),
+            (Map(4), Some(4)),
+            (Push(5), Some(5)),
+            (Set(0), Some(0)),
+            (Bignum("1".into()), None),
+            (BlobError("ERR".into()), None),
+            (BlobString("x".into()), None),
+            (Boolean(true), None),
+            (Double(1.5.into()), None),
+            (Integer(1), None),
+            (Nil, None),
+            (SimpleError("ERR".into()), None),
+            (SimpleString("OK".into()), None),
+            (Unknown(b'@', "x".into()), None),
+            (Verbatim("txt".into(), "x".into()), None),
+        ];
+        for (frame, expected) in cases {
+            assert_eq!(frame.aggregate_len(), expected);
+            assert_eq!(frame.is_aggregate(), expected.is_some());
+        }
+    }
+
+    #[test]
+    fn as_frame_ref() {
+        let cases = [
+            (RespFrame::Array(2), RespFrameRef::Array(2)),
+            (
+                RespFrame::BlobString("x".into()),
+                RespFrameRef::BlobString(b"x"),
+            ),
+            (
+                RespFrame::Double(1.5.into()),
+                RespFrameRef::Double(1.5.into()),
+            ),
+            (RespFrame::Nil, RespFrameRef::Nil),
+            (
+                RespFrame::Unknown(b'@', "x".into()),
+                RespFrameRef::Unknown(b'@', b"x"),
+            ),
+            (
+                RespFrame::Verbatim("txt".into(), "x".into()),
+                RespFrameRef::Verbatim(b"txt", b"x"),
+            ),
+        ];
+        for (frame, expected) in cases {
+            assert_eq!(frame.as_frame_ref(), expected);
+        }
+    }
+
+    #[test]
+    fn clone() {
+        use RespFrame::*;
+
+        let frames = [
+            Array(2),
+            Attribute(3),
+            Bignum("1".into()),
+            BlobError("ERR".into()),
+            BlobString("x".into()),
+            Boolean(true),
+            Double(1.5.into()),
+            Integer(1),
+            Map(4),
+            Nil,
+            Push(5),
+            Set(0),
+            SimpleError("ERR".into()),
+            SimpleString("OK".into()),
+            Unknown(b'@', "x".into()),
+            Verbatim("txt".into(), "x".into()),
+        ];
+        for frame in frames {
+            assert_eq!(frame.clone(), frame);
+        }
+    }
+}
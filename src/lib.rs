@@ -17,6 +17,15 @@
 //! You can also use [`RespReader::value`], which will buffer values and return a whole tree of
 //! frames for arrays, maps, sets, etc. This is primarily meant for testing purposes, but could
 //! also be useful in cases where performance isn't super important.
+//!
+//! # Without `std`
+//!
+//! The `std` feature is enabled by default. Without it, only [`RespParser`] and the types it uses
+//! are available, built on `core` and `alloc` alone.
+
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+
+extern crate alloc;
 
 /// Conveniently create a [`RespPrimitive`]
 #[macro_export]
@@ -53,6 +62,14 @@ macro_rules! resp {
         use $crate::RespValue;
         RespValue::Bignum($x.into())
     }};
+    ( ( blob $x:expr ) ) => {{
+        use $crate::RespValue;
+        RespValue::String($x.into())
+    }};
+    ( ( + $x:expr ) ) => {{
+        use $crate::RespValue;
+        RespValue::SimpleString($x.into())
+    }};
     ( { } ) => {{
         use $crate::RespValue;
         use std::collections::BTreeMap;
@@ -105,24 +122,46 @@ macro_rules! resp {
     }};
 }
 
+#[cfg(feature = "codec")]
+mod codec;
 mod config;
 mod error;
 mod frame;
+#[cfg(feature = "std")]
+mod message;
+mod parser;
+#[cfg(feature = "std")]
 mod primitive;
+#[cfg(feature = "std")]
 mod reader;
+#[cfg(feature = "std")]
 mod request;
+#[cfg(feature = "std")]
 mod splitter;
+#[cfg(feature = "std")]
 mod value;
 mod version;
+#[cfg(feature = "std")]
 mod writer;
 
-pub use config::RespConfig;
-pub use error::RespError;
-pub use frame::RespFrame;
+#[cfg(feature = "codec")]
+pub use codec::{RespDecoder, RespEncoder};
+pub use config::{RespConfig, RespConfigBuilder};
+pub use error::{RespError, RespErrorKind};
+pub use frame::{RespFrame, RespFrameRef};
+#[cfg(feature = "std")]
+pub use message::RespMessage;
+pub use parser::RespParser;
+#[cfg(feature = "std")]
 pub use primitive::RespPrimitive;
-pub use reader::RespReader;
+#[cfg(feature = "std")]
+pub use reader::{parse_frame, validate_frame, RespReader};
+#[cfg(feature = "std")]
 pub use request::RespRequest;
+#[cfg(feature = "std")]
 use splitter::Splitter;
+#[cfg(feature = "std")]
 pub use value::RespValue;
 pub use version::RespVersion;
-pub use writer::RespWriter;
+#[cfg(feature = "std")]
+pub use writer::{ArrayWriter, RespWriter};
@@ -0,0 +1,20 @@
+use crate::RespValue;
+
+/// A message from a server, either a reply to a command or an out of band push.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RespMessage {
+    /// A reply to a command.
+    Reply(RespValue),
+
+    /// The elements of a push.
+    Push(Vec<RespValue>),
+}
+
+impl From<RespValue> for RespMessage {
+    fn from(value: RespValue) -> Self {
+        match value {
+            RespValue::Push(values) => RespMessage::Push(values),
+            value => RespMessage::Reply(value),
+        }
+    }
+}
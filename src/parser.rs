@@ -0,0 +1,663 @@
+use crate::{RespConfig, RespError, RespFrame, RespFrameRef, RespVersion};
+use bytes::{Bytes, BytesMut};
+use core::{cmp, mem, ops::Range, str};
+use ordered_float::OrderedFloat;
+
+/// The most digits in a size, enough for any [`usize`] with a few leading zeros.
+pub(crate) const SIZE_DIGITS_LIMIT: usize = 24;
+
+/// An incremental parser for [`RespFrame`]s, without an async runtime.
+///
+/// Bytes are pushed in as they arrive, and frames are taken out once they're complete. Parsing
+/// picks up where it left off, so a frame split across many pushes is still only parsed once.
+/// This only needs `core` and `alloc`, and is available without the `std` feature.
+///
+/// ```
+/// # use respite::{RespConfig, RespFrame, RespParser};
+/// let mut parser = RespParser::new(RespConfig::default());
+/// parser.push_bytes(b"$3\r\nh");
+/// assert_eq!(parser.try_frame().unwrap(), None);
+/// parser.push_bytes(b"i!\r\n");
+/// assert_eq!(parser.try_frame().unwrap(), Some(RespFrame::BlobString("hi!".into())));
+/// ```
+#[derive(Debug)]
+pub struct RespParser {
+    /// Input that hasn't been parsed yet.
+    buffer: BytesMut,
+
+    /// Reader config.
+    config: RespConfig,
+
+    /// Progress through the frame at the start of `buffer`.
+    parser: FrameParser,
+
+    /// The current version, enforced when [`RespConfig::strict_version`] is enabled.
+    version: RespVersion,
+}
+
+impl RespParser {
+    /// Create a new [`RespParser`] from a [`RespConfig`].
+    pub fn new(config: RespConfig) -> Self {
+        Self {
+            buffer: BytesMut::new(),
+            config,
+            parser: FrameParser::default(),
+            version: RespVersion::V2,
+        }
+    }
+
+    /// Add input to the end of the buffer.
+    pub fn push_bytes(&mut self, input: &[u8]) {
+        self.buffer.extend_from_slice(input);
+    }
+
+    /// The number of bytes pushed but not yet parsed.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// The current version.
+    pub fn version(&self) -> RespVersion {
+        self.version
+    }
+
+    /// Set the current version, enforced when [`RespConfig::strict_version`] is enabled.
+    ///
+    /// The default is [`RespVersion::V2`].
+    pub fn set_version(&mut self, version: RespVersion) {
+        self.version = version;
+    }
+
+    /// Parse the next frame, or return `None` if the buffer doesn't contain a whole frame yet.
+    ///
+    /// Partial frames are left in the buffer until the rest is pushed.
+    pub fn try_frame(&mut self) -> Result<Option<RespFrame>, RespError> {
+        let parsed = self
+            .parser
+            .parse(&self.buffer, &self.config, self.version)?;
+        Ok(parsed.map(|(frame, len)| frame.into_frame(&self.buffer.split_to(len).freeze())))
+    }
+}
+
+/// Where a [`FrameParser`] is in the current frame.
+#[derive(Clone, Debug, Default)]
+enum State {
+    /// Waiting for the type of the next frame.
+    #[default]
+    Start,
+
+    /// Reading the size of an aggregate or blob of type `kind`, with `digits` digits so far.
+    Size { kind: u8, size: u64, digits: usize },
+
+    /// Reading the `\n` after the size of an aggregate or blob of type `kind`.
+    SizeEnd { kind: u8, size: u64 },
+
+    /// Reading a line of type `kind`, with the first `scanned` bytes known not to end it.
+    Line {
+        kind: u8,
+        limit: usize,
+        scanned: usize,
+    },
+
+    /// Reading `size` bytes of a blob of type `kind`.
+    Blob { kind: u8, size: usize },
+
+    /// Reading the value of a boolean.
+    Boolean,
+
+    /// Reading exactly `expected` to end `frame`.
+    End {
+        expected: &'static [u8],
+        frame: Parsed,
+    },
+}
+
+/// A resumable parser for the frame at the start of some input, which the caller keeps.
+///
+/// The caller adds input to the end and calls [`FrameParser::parse`] again until it returns a
+/// frame, without removing anything from the start in the meantime. Parsing resumes where it
+/// left off, so each byte is only examined once.
+#[derive(Debug, Default)]
+pub(crate) struct FrameParser {
+    /// The number of bytes of the current frame already parsed.
+    offset: usize,
+
+    /// Where the parser is in the current frame.
+    state: State,
+}
+
+impl FrameParser {
+    /// Parse the frame at the start of `input`, returning it with its length once it's whole.
+    ///
+    /// The parser is ready for the next frame after returning one or an error.
+    pub(crate) fn parse(
+        &mut self,
+        input: &[u8],
+        config: &RespConfig,
+        version: RespVersion,
+    ) -> Result<Option<(Parsed, usize)>, RespError> {
+        let result = self.resume(input, config, version);
+        if !matches!(result, Ok(None)) {
+            self.reset();
+        }
+        result
+    }
+
+    /// Forget any progress through the current frame.
+    pub(crate) fn reset(&mut self) {
+        self.offset = 0;
+        self.state = State::Start;
+    }
+
+    /// The least number of bytes that `input` needs before parsing can make progress.
+    #[cfg(feature = "std")]
+    pub(crate) fn wanted(&self, input: &[u8]) -> usize {
+        match self.state {
+            State::Blob { size, .. } => {
+                cmp::max((self.offset + size).saturating_sub(input.len()), 1)
+            }
+            _ => 1,
+        }
+    }
+
+    /// Parse as far as `input` allows.
+    fn resume(
+        &mut self,
+        input: &[u8],
+        config: &RespConfig,
+        version: RespVersion,
+    ) -> Result<Option<(Parsed, usize)>, RespError> {
+        use State::*;
+
+        let strict = |v| version == v && config.strict_version();
+
+        loop {
+            match self.state {
+                Start | Boolean | Size { .. } | SizeEnd { .. } | End { .. }
+                    if self.offset == input.len() =>
+                {
+                    return Ok(None);
+                }
+                Start => {
+                    let kind = input[0];
+                    if strict(RespVersion::V2) && !matches!(kind, b'*' | b'$' | b'-' | b':' | b'+')
+                    {
+                        return Err(RespError::Version);
+                    }
+                    self.state = match kind {
+                        b'*' | b'$' | b'=' | b'!' | b'%' | b'>' | b'~' | b'|' => Size {
+                            kind,
+                            size: 0,
+                            digits: 0,
+                        },
+                        b'(' | b',' | b'-' | b':' | b'+' => Line {
+                            kind,
+                            limit: config.inline_limit(),
+                            scanned: 0,
+                        },
+                        b'#' => Boolean,
+                        b'_' => End {
+                            expected: b"\r\n",
+                            frame: Parsed::Nil,
+                        },
+                        kind if config.unknown_types() => Line {
+                            kind,
+                            limit: config.inline_limit(),
+                            scanned: 0,
+                        },
+                        kind => return Err(RespError::UnknownType(kind)),
+                    };
+                    self.offset = 1;
+                }
+                Size { kind, size, digits } => {
+                    let byte = input[self.offset];
+                    self.offset += 1;
+                    self.state = match byte {
+                        b'-' if digits == 0 && matches!(kind, b'*' | b'$') => {
+                            if strict(RespVersion::V3) {
+                                return Err(RespError::Version);
+                            }
+                            End {
+                                expected: b"1\r\n",
+                                frame: Parsed::Nil,
+                            }
+                        }
+                        b'\r' if digits > 0 => SizeEnd { kind, size },
+                        b'0'..=b'9' if digits < SIZE_DIGITS_LIMIT => Size {
+                            kind,
+                            size: size
+                                .checked_mul(10)
+                                .and_then(|size| size.checked_add((byte - b'0').into()))
+                                .ok_or_else(|| too_big_size(kind))?,
+                            digits: digits + 1,
+                        },
+                        b'0'..=b'9' => return Err(too_big_size(kind)),
+                        _ => return Err(invalid_size(kind)),
+                    };
+                }
+                SizeEnd { kind, size } => {
+                    match input[self.offset] {
+                        b'\n' => self.offset += 1,
+                        got => return Err(RespError::Unexpected(b'\n', got)),
+                    }
+                    if !matches!(kind, b'$' | b'=' | b'!') {
+                        let size = usize::try_from(size).map_err(|_| RespError::InvalidLength)?;
+                        return Ok(Some((Parsed::Aggregate(kind, size), self.offset)));
+                    }
+                    if size > config.blob_limit() as u64 {
+                        return Err(RespError::TooBigBlob);
+                    }
+                    let size = usize::try_from(size).map_err(|_| RespError::TooBigBlob)?;
+                    if kind == b'=' && size < 4 {
+                        return Err(RespError::InvalidVerbatim);
+                    }
+                    self.state = Blob { kind, size };
+                }
+                Line {
+                    kind,
+                    limit,
+                    scanned,
+                } => {
+                    // A line of exactly `limit` bytes is allowed, so its `\r` may be just past it.
+                    let line = &input[self.offset..];
+                    let to = cmp::min(limit.saturating_add(1), line.len());
+                    let index = if config.lenient_newlines() {
+                        memchr::memchr2(b'\r', b'\n', &line[scanned..to])
+                    } else {
+                        memchr::memchr(b'\r', &line[scanned..to])
+                    };
+
+                    let Some(index) = index else {
+                        if line.len() > limit {
+                            return Err(RespError::TooBigInline);
+                        }
+                        self.state = Line {
+                            kind,
+                            limit,
+                            scanned: to,
+                        };
+                        return Ok(None);
+                    };
+
+                    let end = self.offset + scanned + index;
+                    let frame = Parsed::Data(kind, self.offset..end);
+                    self.offset = end;
+                    if input[end] == b'\n' {
+                        self.offset += 1;
+                        return finish(frame, input, config).map(|frame| Some((frame, end + 1)));
+                    }
+                    self.state = End {
+                        expected: b"\r\n",
+                        frame,
+                    };
+                }
+                Blob { kind, size } => {
+                    let start = self.offset;
+                    if input.len() - start < size {
+                        return Ok(None);
+                    }
+                    self.offset += size;
+                    let frame = if kind == b'=' {
+                        if input[start + 3] != b':' {
+                            return Err(RespError::InvalidVerbatim);
+                        }
+                        Parsed::Verbatim(start..start + 3, start + 4..self.offset)
+                    } else {
+                        Parsed::Data(kind, start..self.offset)
+                    };
+                    self.state = End {
+                        expected: b"\r\n",
+                        frame,
+                    };
+                }
+                Boolean => {
+                    let value = match input[self.offset] {
+                        b't' => true,
+                        b'f' => false,
+                        b'T' if config.lenient_booleans() => true,
+                        b'F' if config.lenient_booleans() => false,
+                        _ => return Err(RespError::InvalidBoolean),
+                    };
+                    self.offset += 1;
+                    self.state = End {
+                        expected: b"\r\n",
+                        frame: Parsed::Boolean(value),
+                    };
+                }
+                End {
+                    ref mut expected, ..
+                } => {
+                    let got = input[self.offset];
+                    if got != expected[0] {
+                        return Err(RespError::Unexpected(expected[0], got));
+                    }
+                    self.offset += 1;
+                    *expected = &expected[1..];
+                    if expected.is_empty() {
+                        let State::End { frame, .. } = mem::take(&mut self.state) else {
+                            unreachable!();
+                        };
+                        return finish(frame, input, config)
+                            .map(|frame| Some((frame, self.offset)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The error for an invalid size of an aggregate or blob of type `kind`.
+pub(crate) fn invalid_size(kind: u8) -> RespError {
+    match kind {
+        b'$' | b'=' | b'!' => RespError::InvalidBlobLength,
+        _ => RespError::InvalidLength,
+    }
+}
+
+/// The error for a size of an aggregate or blob of type `kind` with too many digits.
+pub(crate) fn too_big_size(kind: u8) -> RespError {
+    match kind {
+        b'$' | b'=' | b'!' => RespError::TooBigBlob,
+        _ => RespError::InvalidLength,
+    }
+}
+
+/// Finish a frame once all of it has been read, checking and converting any line it contains.
+fn finish(frame: Parsed, input: &[u8], config: &RespConfig) -> Result<Parsed, RespError> {
+    Ok(match frame {
+        Parsed::Data(b'(', range) => {
+            let value = &input[range.clone()];
+            let digits = value.strip_prefix(b"-").unwrap_or(value);
+            if digits.len() > config.bignum_limit() {
+                return Err(RespError::TooBigBignum);
+            }
+            Parsed::Data(b'(', range)
+        }
+        Parsed::Data(b',', range) => {
+            // Parsing accepts an optional sign, and `inf` and `nan` in any case.
+            let value = str::from_utf8(&input[range])
+                .ok()
+                .and_then(|x| x.parse().ok())
+                .ok_or(RespError::InvalidDouble)?;
+            Parsed::Double(value)
+        }
+        Parsed::Data(b':', range) => {
+            let value = str::from_utf8(&input[range])
+                .ok()
+                .and_then(|x| x.parse().ok())
+                .ok_or(RespError::InvalidInteger)?;
+            Parsed::Integer(value)
+        }
+        frame => frame,
+    })
+}
+
+/// A whole frame at the start of some input, with any data as ranges of the input.
+#[derive(Clone, Debug)]
+pub(crate) enum Parsed {
+    /// An aggregate of type `kind` and `size`.
+    Aggregate(u8, usize),
+    Boolean(bool),
+    /// A line or blob of type `kind`.
+    Data(u8, Range<usize>),
+    Double(OrderedFloat<f64>),
+    Integer(i64),
+    Nil,
+    Verbatim(Range<usize>, Range<usize>),
+}
+
+impl Parsed {
+    /// Borrow this frame's data from `input`.
+    pub(crate) fn as_frame_ref<'a>(&self, input: &'a [u8]) -> RespFrameRef<'a> {
+        use RespFrameRef::*;
+
+        match self {
+            Parsed::Aggregate(b'*', size) => Array(*size),
+            Parsed::Aggregate(b'|', size) => Attribute(*size),
+            Parsed::Aggregate(b'%', size) => Map(*size),
+            Parsed::Aggregate(b'>', size) => Push(*size),
+            Parsed::Aggregate(_, size) => Set(*size),
+            Parsed::Boolean(value) => Boolean(*value),
+            Parsed::Data(b'(', range) => Bignum(&input[range.clone()]),
+            Parsed::Data(b'!', range) => BlobError(&input[range.clone()]),
+            Parsed::Data(b'$', range) => BlobString(&input[range.clone()]),
+            Parsed::Data(b'-', range) => SimpleError(&input[range.clone()]),
+            Parsed::Data(b'+', range) => SimpleString(&input[range.clone()]),
+            Parsed::Data(kind, range) => Unknown(*kind, &input[range.clone()]),
+            Parsed::Double(value) => Double(*value),
+            Parsed::Integer(value) => Integer(*value),
+            Parsed::Nil => Nil,
+            Parsed::Verbatim(format, value) => {
+                Verbatim(&input[format.clone()], &input[value.clone()])
+            }
+        }
+    }
+
+    /// Convert this frame into a [`RespFrame`] that shares its data with `input`.
+    pub(crate) fn into_frame(self, input: &Bytes) -> RespFrame {
+        use RespFrameRef::*;
+
+        match self.as_frame_ref(input) {
+            Array(size) => RespFrame::Array(size),
+            Attribute(size) => RespFrame::Attribute(size),
+            Bignum(value) => RespFrame::Bignum(input.slice_ref(value)),
+            BlobError(value) => RespFrame::BlobError(input.slice_ref(value)),
+            BlobString(value) => RespFrame::BlobString(input.slice_ref(value)),
+            Boolean(value) => RespFrame::Boolean(value),
+            Double(value) => RespFrame::Double(value),
+            Integer(value) => RespFrame::Integer(value),
+            Map(size) => RespFrame::Map(size),
+            Nil => RespFrame::Nil,
+            Push(size) => RespFrame::Push(size),
+            Set(size) => RespFrame::Set(size),
+            SimpleError(value) => RespFrame::SimpleError(input.slice_ref(value)),
+            SimpleString(value) => RespFrame::SimpleString(input.slice_ref(value)),
+            Unknown(kind, value) => RespFrame::Unknown(kind, input.slice_ref(value)),
+            Verbatim(format, value) => {
+                RespFrame::Verbatim(input.slice_ref(format), input.slice_ref(value))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytewise() -> Result<(), RespError> {
+        let input = b"*2\r\n$5\r\nhello\r\n:12\r\n,1.5\r\n";
+        let mut parser = RespParser::new(RespConfig::default());
+        let mut frames = Vec::new();
+        for byte in input {
+            parser.push_bytes(&[*byte]);
+            while let Some(frame) = parser.try_frame()? {
+                frames.push(frame);
+            }
+        }
+        assert_eq!(
+            frames,
+            vec![
+                RespFrame::Array(2),
+                RespFrame::BlobString("hello".into()),
+                RespFrame::Integer(12),
+                RespFrame::Double(1.5.into()),
+            ]
+        );
+        assert_eq!(parser.buffered(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn partial() -> Result<(), RespError> {
+        let mut parser = RespParser::new(RespConfig::default());
+        assert_eq!(parser.try_frame()?, None);
+        parser.push_bytes(b"+OK\r\n:1");
+        assert_eq!(
+            parser.try_frame()?,
+            Some(RespFrame::SimpleString("OK".into()))
+        );
+        assert_eq!(parser.try_frame()?, None);
+        assert_eq!(parser.buffered(), 2);
+        parser.push_bytes(b"\r\n");
+        assert_eq!(parser.try_frame()?, Some(RespFrame::Integer(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn error() {
+        let mut parser = RespParser::new(RespConfig::default());
+        parser.push_bytes(b"?\r\n");
+        assert!(matches!(
+            parser.try_frame(),
+            Err(RespError::UnknownType(b'?'))
+        ));
+
+        let config = RespConfig::builder().strict_version(true).build();
+        let mut parser = RespParser::new(config);
+        parser.push_bytes(b"_\r\n");
+        assert!(matches!(parser.try_frame(), Err(RespError::Version)));
+    }
+
+    #[test]
+    fn version() -> Result<(), RespError> {
+        let mut config = RespConfig::default();
+        config.set_strict_version(true);
+        let mut parser = RespParser::new(config);
+        assert_eq!(parser.version(), RespVersion::V2);
+        parser.push_bytes(b"_\r\n");
+        assert!(matches!(parser.try_frame(), Err(RespError::Version)));
+
+        let mut parser = RespParser::new(parser.config.clone());
+        parser.set_version(RespVersion::V3);
+        assert_eq!(parser.version(), RespVersion::V3);
+        parser.push_bytes(b"_\r\n");
+        assert_eq!(parser.try_frame()?, Some(RespFrame::Nil));
+        Ok(())
+    }
+
+    #[test]
+    fn types() -> Result<(), RespError> {
+        let input = b"%1\r\n|1\r\n~1\r\n>1\r\n*-1\r\n$-1\r\n_\r\n#t\r\n#f\r\n(-12\r\n!3\r\nERR\r\n-ERR\r\n+OK\r\n=7\r\ntxt:abc\r\n";
+        let expected = vec![
+            RespFrame::Map(1),
+            RespFrame::Attribute(1),
+            RespFrame::Set(1),
+            RespFrame::Push(1),
+            RespFrame::Nil,
+            RespFrame::Nil,
+            RespFrame::Nil,
+            RespFrame::Boolean(true),
+            RespFrame::Boolean(false),
+            RespFrame::Bignum("-12".into()),
+            RespFrame::BlobError("ERR".into()),
+            RespFrame::SimpleError("ERR".into()),
+            RespFrame::SimpleString("OK".into()),
+            RespFrame::Verbatim("txt".into(), "abc".into()),
+        ];
+
+        for split in 0..=input.len() {
+            let mut parser = RespParser::new(RespConfig::default());
+            let mut frames = Vec::new();
+            for chunk in [&input[..split], &input[split..]] {
+                parser.push_bytes(chunk);
+                while let Some(frame) = parser.try_frame()? {
+                    frames.push(frame);
+                }
+            }
+            assert_eq!(frames, expected);
+            assert_eq!(parser.buffered(), 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn resume_blob() -> Result<(), RespError> {
+        let mut parser = RespParser::new(RespConfig::default());
+        parser.push_bytes(b"$10\r\n0123");
+        assert_eq!(parser.try_frame()?, None);
+        assert!(matches!(parser.parser.state, State::Blob { size: 10, .. }));
+        assert_eq!(parser.parser.offset, 5);
+
+        parser.push_bytes(b"456789\r");
+        assert_eq!(parser.try_frame()?, None);
+        assert!(matches!(parser.parser.state, State::End { .. }));
+        assert_eq!(parser.parser.offset, 16);
+
+        parser.push_bytes(b"\n");
+        assert_eq!(
+            parser.try_frame()?,
+            Some(RespFrame::BlobString("0123456789".into()))
+        );
+        assert!(matches!(parser.parser.state, State::Start));
+        assert_eq!(parser.parser.offset, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn resume_line() -> Result<(), RespError> {
+        let mut parser = RespParser::new(RespConfig::default());
+        parser.push_bytes(&[b'+'; 101]);
+        assert_eq!(parser.try_frame()?, None);
+        assert!(matches!(
+            parser.parser.state,
+            State::Line { scanned: 100, .. }
+        ));
+
+        parser.push_bytes(b"\r\n");
+        assert_eq!(
+            parser.try_frame()?,
+            Some(RespFrame::SimpleString(vec![b'+'; 100].into()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let cases: [(&[u8], RespError); 10] = [
+            (b"*x\r\n", RespError::InvalidLength),
+            (b"*\r\n", RespError::InvalidLength),
+            (b"$x\r\n", RespError::InvalidBlobLength),
+            (b"*-2\r\n", RespError::Unexpected(b'1', b'2')),
+            (b"*1\rx", RespError::Unexpected(b'\n', b'x')),
+            (b"#x\r\n", RespError::InvalidBoolean),
+            (b":1.5\r\n", RespError::InvalidInteger),
+            (b",x\r\n", RespError::InvalidDouble),
+            (b"=3\r\nabc\r\n", RespError::InvalidVerbatim),
+            (b"$1\r\nab", RespError::Unexpected(b'\r', b'b')),
+        ];
+        for (input, expected) in cases {
+            let mut parser = RespParser::new(RespConfig::default());
+            parser.push_bytes(input);
+            let error = parser.try_frame().unwrap_err();
+            assert_eq!(format!("{error:?}"), format!("{expected:?}"));
+            assert!(matches!(parser.parser.state, State::Start));
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let mut config = RespConfig::default();
+        config.set_inline_limit(3);
+        config.set_blob_limit(3);
+        config.set_bignum_limit(2);
+
+        let cases: [(&[u8], RespError); 3] = [
+            (b"+abcd", RespError::TooBigInline),
+            (b"$4\r\n", RespError::TooBigBlob),
+            (b"(123\r\n", RespError::TooBigBignum),
+        ];
+        for (input, expected) in cases {
+            let mut parser = RespParser::new(config.clone());
+            parser.push_bytes(input);
+            let error = parser.try_frame().unwrap_err();
+            assert_eq!(format!("{error:?}"), format!("{expected:?}"));
+        }
+
+        let mut parser = RespParser::new(config);
+        parser.push_bytes(b"+abc\r\n$3\r\nabc\r\n(-12\r\n");
+        assert!(parser.try_frame().is_ok());
+        assert!(parser.try_frame().is_ok());
+        assert!(parser.try_frame().is_ok());
+    }
+}
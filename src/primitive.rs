@@ -1,7 +1,13 @@
-use crate::{RespError, RespValue};
+use crate::{
+    value::{blob_size, integer_size, nil_size},
+    RespError, RespValue, RespVersion,
+};
 use bytes::Bytes;
 
 /// A primitive value that can be used as the key for a map or set.
+///
+/// Doubles aren't primitives, so maps and sets never have `NaN` keys with surprising ordering.
+/// Reading a map or set with a double key returns [`RespError::RespPrimitive`].
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum RespPrimitive {
     Integer(i64),
@@ -9,6 +15,61 @@ pub enum RespPrimitive {
     String(Bytes),
 }
 
+impl RespPrimitive {
+    /// Extract the bytes of this primitive if it's a string.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        if let RespPrimitive::String(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Calculate the number of bytes this primitive occupies when written in `version`.
+    pub fn byte_size(&self, version: RespVersion) -> usize {
+        match self {
+            RespPrimitive::Integer(value) => integer_size(*value),
+            RespPrimitive::Nil => nil_size(version),
+            RespPrimitive::String(value) => blob_size(value.len()),
+        }
+    }
+
+    /// Extract an [`i64`] if this primitive is an integer.
+    pub fn as_i64(&self) -> Option<i64> {
+        if let RespPrimitive::Integer(value) = self {
+            Some(*value)
+        } else {
+            None
+        }
+    }
+
+    /// Extract a [`str`] if this primitive is a valid UTF-8 string.
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_bytes()
+            .and_then(|value| std::str::from_utf8(value).ok())
+    }
+}
+
+impl std::fmt::Display for RespPrimitive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RespPrimitive::Integer(value) => write!(f, "{}", value),
+            RespPrimitive::Nil => write!(f, "(nil)"),
+            RespPrimitive::String(value) => {
+                write!(f, "\"")?;
+                for &b in value.iter() {
+                    match b {
+                        b'"' | b'\\' => write!(f, "\\{}", char::from(b))?,
+                        b' ' | b'!'..=b'~' => write!(f, "{}", char::from(b))?,
+                        _ => write!(f, "\\x{:02x}", b)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+        }
+    }
+}
+
 impl From<i64> for RespPrimitive {
     fn from(value: i64) -> Self {
         RespPrimitive::Integer(value)
@@ -27,6 +88,24 @@ impl From<String> for RespPrimitive {
     }
 }
 
+impl From<Bytes> for RespPrimitive {
+    fn from(value: Bytes) -> Self {
+        RespPrimitive::String(value)
+    }
+}
+
+impl From<Vec<u8>> for RespPrimitive {
+    fn from(value: Vec<u8>) -> Self {
+        RespPrimitive::String(value.into())
+    }
+}
+
+impl<const N: usize> From<&'static [u8; N]> for RespPrimitive {
+    fn from(value: &'static [u8; N]) -> Self {
+        RespPrimitive::String((&value[..]).into())
+    }
+}
+
 impl TryFrom<RespValue> for RespPrimitive {
     type Error = RespError;
 
@@ -35,8 +114,88 @@ impl TryFrom<RespValue> for RespPrimitive {
         Ok(match value {
             RespValue::Integer(value) => Integer(value),
             RespValue::Nil => RespPrimitive::Nil,
-            RespValue::String(value) => String(value),
+            RespValue::SimpleString(value) | RespValue::String(value) => String(value),
             _ => return Err(RespError::RespPrimitive),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn accessors() {
+        let value = RespPrimitive::from("abc");
+        assert_eq!(value.as_bytes(), Some(&b"abc"[..]));
+        assert_eq!(value.as_str(), Some("abc"));
+        assert_eq!(value.as_i64(), None);
+
+        let value = RespPrimitive::from(b"\xff");
+        assert_eq!(value.as_bytes(), Some(&b"\xff"[..]));
+        assert_eq!(value.as_str(), None);
+        assert_eq!(value.as_i64(), None);
+
+        let value = RespPrimitive::Integer(23);
+        assert_eq!(value.as_bytes(), None);
+        assert_eq!(value.as_str(), None);
+        assert_eq!(value.as_i64(), Some(23));
+
+        let value = RespPrimitive::Nil;
+        assert_eq!(value.as_bytes(), None);
+        assert_eq!(value.as_str(), None);
+        assert_eq!(value.as_i64(), None);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(RespPrimitive::Integer(-23).to_string(), "-23");
+        assert_eq!(RespPrimitive::Nil.to_string(), "(nil)");
+        assert_eq!(RespPrimitive::from("a b").to_string(), "\"a b\"");
+        assert_eq!(
+            RespPrimitive::from(b"\"\\\x00\r\n\xff").to_string(),
+            "\"\\\"\\\\\\x00\\x0d\\x0a\\xff\""
+        );
+    }
+
+    #[test]
+    fn from_bytes() {
+        let value = RespPrimitive::from(Bytes::from_static(b"\x00\xff"));
+        assert_eq!(
+            value,
+            RespPrimitive::String(Bytes::from_static(b"\x00\xff"))
+        );
+
+        let value = RespPrimitive::from(vec![0u8, 255u8]);
+        assert_eq!(
+            value,
+            RespPrimitive::String(Bytes::from_static(b"\x00\xff"))
+        );
+
+        let value = RespPrimitive::from(b"\x00\xff");
+        assert_eq!(
+            value,
+            RespPrimitive::String(Bytes::from_static(b"\x00\xff"))
+        );
+    }
+
+    #[test]
+    fn byte_keys() {
+        // Bytes is a false positive here.
+        // <https://rust-lang.github.io/rust-clippy/master/index.html#mutable_key_type>
+        #[allow(clippy::mutable_key_type)]
+        let mut map = BTreeMap::new();
+        map.insert(Bytes::from_static(b"\x00").into(), RespValue::Integer(1));
+        map.insert(vec![1u8].into(), RespValue::Integer(2));
+        assert_eq!(RespValue::Map(map), resp! { {b"\x00" => 1, b"\x01" => 2} });
+
+        // Bytes is a false positive here.
+        // <https://rust-lang.github.io/rust-clippy/master/index.html#mutable_key_type>
+        #[allow(clippy::mutable_key_type)]
+        let mut set = BTreeSet::new();
+        set.insert(Bytes::from_static(b"\x00").into());
+        set.insert(vec![1u8].into());
+        assert_eq!(RespValue::Set(set), resp! { {b"\x00", b"\x01"} });
+    }
+}
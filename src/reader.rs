@@ -1,11 +1,20 @@
-use crate::{RespConfig, RespError, RespFrame, RespRequest, RespValue, Splitter};
-use bytes::{Buf, Bytes, BytesMut};
+use crate::{
+    parser::{invalid_size, too_big_size, FrameParser, Parsed, SIZE_DIGITS_LIMIT},
+    RespConfig, RespError, RespFrame, RespFrameRef, RespMessage, RespPrimitive, RespRequest,
+    RespValue, RespVersion, Splitter,
+};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::{
     cmp,
     collections::{BTreeMap, BTreeSet},
+    future::Future,
     marker::Unpin,
+    mem,
+    pin::{pin, Pin},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
 };
-use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::io::{AsyncRead, AsyncReadExt, Empty};
 
 /// A wrapper for [`AsyncRead`] to allow reading a RESP stream, mainly in three ways.
 ///
@@ -17,23 +26,136 @@ pub struct RespReader<Inner: AsyncRead + Unpin> {
     /// The input buffer.
     buffer: BytesMut,
 
+    /// Attributes preceding the last value read with [`RespReader::value`].
+    attributes: Option<BTreeMap<RespPrimitive, RespValue>>,
+
     /// Reader config.
     config: RespConfig,
 
+    /// The length of the frame last borrowed with [`RespReader::frame_ref`], which is still at
+    /// the start of `buffer` until the next read.
+    borrowed: usize,
+
+    /// The total number of frames read, counting each inline request as one.
+    frames: u64,
+
     /// The inner `AsyncRead`.
     inner: Inner,
+
+    /// Progress through the frame at the start of `buffer`.
+    parser: FrameParser,
+
+    /// Pushes received while reading replies with [`RespReader::next_reply`].
+    pushes: Vec<RespValue>,
+
+    /// The total number of bytes read from `inner`.
+    received: usize,
+
+    /// The total number of bytes scanned for the end of a line by [`RespReader::read_line`].
+    #[cfg(test)]
+    scanned: usize,
+
+    /// The current version, enforced when [`RespConfig::strict_version`] is enabled.
+    version: RespVersion,
+}
+
+/// The most space to reserve at once while reading a blob.
+pub(crate) const READ_RESERVE_LIMIT: usize = 1024 * 1024;
+
+/// The least to read at once when more input is needed past the high water mark.
+const READ_MIN_CHUNK: usize = 8 * 1024;
+
+/// Running totals while reading one top-level [`RespValue`].
+#[derive(Debug, Default)]
+struct ValueTotals {
+    /// The number of aggregate elements declared so far.
+    elements: usize,
+
+    /// The number of payload bytes read so far.
+    bytes: usize,
 }
 
 impl<Inner: AsyncRead + Unpin> RespReader<Inner> {
     /// Create a new [`RespReader`] from a byte stream and a [`RespConfig`].
     pub fn new(inner: Inner, config: RespConfig) -> Self {
         Self {
+            attributes: None,
+            borrowed: 0,
             buffer: BytesMut::default(),
             config,
+            frames: 0,
             inner,
+            parser: FrameParser::default(),
+            pushes: Vec::new(),
+            received: 0,
+            #[cfg(test)]
+            scanned: 0,
+            version: RespVersion::V2,
         }
     }
 
+    /// Create a new [`RespReader`] from a byte stream with the default [`RespConfig`].
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespFrame, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let mut reader = RespReader::with_default_config(":1\r\n".as_bytes());
+    /// assert_eq!(reader.frame().await.unwrap(), Some(RespFrame::Integer(1)));
+    /// # });
+    /// ```
+    pub fn with_default_config(inner: Inner) -> Self {
+        Self::new(inner, RespConfig::default())
+    }
+
+    /// Reuse this reader for a new stream, keeping its config and the buffer's capacity.
+    ///
+    /// Any buffered input, pushes, and attributes from the previous stream are discarded. The
+    /// counters are reset to zero, and the version to [`RespVersion::V2`].
+    pub fn reset(&mut self, inner: Inner) {
+        self.attributes = None;
+        self.borrowed = 0;
+        self.buffer.clear();
+        self.frames = 0;
+        self.inner = inner;
+        self.parser.reset();
+        self.pushes.clear();
+        self.received = 0;
+        self.version = RespVersion::V2;
+    }
+
+    /// The number of bytes consumed from the stream so far.
+    ///
+    /// Bytes that have been read from the inner stream but are still buffered aren't counted.
+    pub fn position(&self) -> usize {
+        self.received - self.buffer.len() + self.borrowed
+    }
+
+    /// The total number of bytes read from the stream, including any still buffered.
+    pub fn bytes_read(&self) -> u64 {
+        self.received as u64
+    }
+
+    /// The total number of frames read from the stream.
+    ///
+    /// Each argument of a multibulk request is a frame, and each inline request counts as one.
+    pub fn frames_read(&self) -> u64 {
+        self.frames
+    }
+
+    /// The current version.
+    pub fn version(&self) -> RespVersion {
+        self.version
+    }
+
+    /// Set the current version, enforced when [`RespConfig::strict_version`] is enabled.
+    ///
+    /// The default is [`RespVersion::V2`].
+    pub fn set_version(&mut self, version: RespVersion) {
+        self.version = version;
+    }
+
     /// Call `f` for each [`RespRequest`] received on this stream.
     ///
     /// ```
@@ -66,65 +188,203 @@ impl<Inner: AsyncRead + Unpin> RespReader<Inner> {
         F: FnMut(RespRequest),
     {
         let mut splitter = Splitter::default();
+        while self.request(&mut splitter, f).await? {}
+        Ok(())
+    }
 
-        while let Some(byte) = self.peek().await? {
-            if byte == b'*' {
-                self.require("*").await?;
-                let size = self.read_size().await?;
-                for _ in 0..size {
-                    self.require("$").await?;
-                    let size = self.read_size().await?;
+    /// Read one request, passing each piece of it to `f`.
+    ///
+    /// Returns `false` if the stream ended before another request started.
+    async fn request<F>(&mut self, splitter: &mut Splitter, f: &mut F) -> Result<bool, RespError>
+    where
+        F: FnMut(RespRequest),
+    {
+        let Some(mut byte) = self.peek().await? else {
+            return Ok(false);
+        };
 
-                    if size > self.config.blob_limit() {
-                        return Err(RespError::InvalidBlobLength);
-                    }
+        // Every line is an inline request, even if it looks like a frame.
+        let inline_only = self.config.inline_only();
+
+        if byte == b'|' && self.config.skip_request_attributes() && !inline_only {
+            self.read_value(&mut ValueTotals::default()).await?;
+            byte = self.peek().await?.ok_or(RespError::EndOfInput)?;
+        }
 
-                    let result = self.read_exact(size).await?;
-                    self.require("\r\n").await?;
-                    f(result.into());
+        if byte == b'*' && !inline_only {
+            self.require("*").await?;
+            let size = self.read_size().await?;
+            self.frames += 1;
+            if self.config.multibulk_counts() {
+                f(RespRequest::Multibulk(size));
+            }
+            for _ in 0..size {
+                self.require("$").await?;
+                let size = self.read_blob_size().await?;
+                let data = self.read_exact(size).await?;
+                self.require("\r\n").await?;
+                self.frames += 1;
+                if self.config.sized_arguments() {
+                    f(RespRequest::SizedArgument { size, data });
+                } else {
+                    f(data.into());
                 }
-                f(RespRequest::End);
-                continue;
             }
+            f(RespRequest::End);
+            return Ok(true);
+        }
+
+        if !self.config.inline_enabled() && !inline_only {
+            return Err(RespError::InvalidInline);
+        }
 
-            let line = self.read_line().await?;
-            if splitter.split(&line[..]) {
-                while let Some(argument) = splitter.next() {
-                    f(argument.into());
+        let line = self.read_line().await?;
+        self.frames += 1;
+        if splitter.split(line) {
+            let limit = self.config.inline_poll_limit();
+            let mut count = 0;
+            while let Some(argument) = splitter.next() {
+                f(argument.into());
+                count += 1;
+                if count >= limit {
+                    count = 0;
+                    YieldNow::default().await;
                 }
-                f(RespRequest::End);
-            } else {
-                f(RespRequest::InvalidArgument);
             }
+            f(RespRequest::End);
+        } else {
+            f(RespRequest::InvalidArgument);
         }
 
-        Ok(())
+        Ok(true)
+    }
+
+    /// Read the next whole command, returning its arguments.
+    ///
+    /// Returns [`RespError::InvalidArgument`] if an inline command can't be split into arguments.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "*2\r\n$3\r\nGET\r\n$1\r\na\r\nPING\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let command = reader.next_command().await.unwrap();
+    /// assert_eq!(command, Some(vec!["GET".into(), "a".into()]));
+    /// let command = reader.next_command().await.unwrap();
+    /// assert_eq!(command, Some(vec!["PING".into()]));
+    /// assert_eq!(reader.next_command().await.unwrap(), None);
+    /// # });
+    /// ```
+    pub async fn next_command(&mut self) -> Result<Option<Vec<Bytes>>, RespError> {
+        let mut command = Vec::new();
+        let mut invalid = false;
+        let mut splitter = Splitter::default();
+        let mut f = |request| match request {
+            RespRequest::Argument(data) | RespRequest::SizedArgument { data, .. } => {
+                command.push(data)
+            }
+            RespRequest::InvalidArgument => invalid = true,
+            RespRequest::Error(_) | RespRequest::End | RespRequest::Multibulk(_) => {}
+        };
+
+        if !self.request(&mut splitter, &mut f).await? {
+            return Ok(None);
+        }
+
+        if invalid {
+            return Err(RespError::InvalidArgument);
+        }
+
+        Ok(Some(command))
     }
 
     /// Read the next [`RespValue`] from the stream.
     ///
+    /// A top-level attribute isn't returned as a value. Instead, it's consumed along with the
+    /// value following it, and is available from [`RespReader::last_attributes`].
+    ///
     /// ```
     /// # use tokio::runtime::Runtime;
-    /// # use respite::{RespConfig, RespValue, RespReader};
+    /// # use respite::{RespValue, RespReader};
     /// # let runtime = Runtime::new().unwrap();
     /// # runtime.block_on(async {
     /// let input = "$3\r\nhi!\r\n".as_bytes();
-    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let mut reader = RespReader::with_default_config(input);
     /// let frame = reader.value().await.unwrap();
     /// assert_eq!(frame, Some(RespValue::String("hi!".into())));
     /// # });
     /// ```
     pub async fn value(&mut self) -> Result<Option<RespValue>, RespError> {
+        let mut totals = ValueTotals::default();
+        self.attributes = None;
+        loop {
+            match self.read_value(&mut totals).await? {
+                Some(RespValue::Attribute(attributes)) => self.attributes = Some(attributes),
+                Some(value) => return Ok(Some(value)),
+                None if self.attributes.is_some() => return Err(RespError::EndOfInput),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// The attributes preceding the last value read with [`RespReader::value`], if any.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespValue, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "|1\r\n+ttl\r\n:3\r\n+hi!\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let value = reader.value().await.unwrap();
+    /// assert_eq!(value, Some(RespValue::SimpleString("hi!".into())));
+    /// assert_eq!(reader.last_attributes().unwrap().len(), 1);
+    /// # });
+    /// ```
+    pub fn last_attributes(&self) -> Option<&BTreeMap<RespPrimitive, RespValue>> {
+        self.attributes.as_ref()
+    }
+
+    /// Read the next [`RespValue`], adding to the running `totals` for the top-level value.
+    async fn read_value(
+        &mut self,
+        totals: &mut ValueTotals,
+    ) -> Result<Option<RespValue>, RespError> {
+        use RespFrame::*;
+
         let Some(frame) = self.frame().await? else {
             return Ok(None);
         };
 
-        use RespFrame::*;
+        match frame {
+            // Each entry of a map or attribute is a key and a value.
+            Attribute(size) | Map(size) => self.add_elements(totals, size.saturating_mul(2))?,
+            Array(size) | Push(size) | Set(size) => self.add_elements(totals, size)?,
+            _ => {}
+        }
+
+        let bytes = match &frame {
+            Bignum(value)
+            | BlobError(value)
+            | BlobString(value)
+            | SimpleError(value)
+            | SimpleString(value)
+            | Unknown(_, value) => value.len(),
+            Verbatim(format, value) => format.len() + value.len(),
+            _ => 0,
+        };
+        totals.bytes = totals.bytes.saturating_add(bytes);
+        if totals.bytes > self.config.total_limit() {
+            return Err(RespError::TooLarge);
+        }
+
         let result = match frame {
             Array(size) => {
                 let mut array = Vec::new();
                 for _ in 0..size {
-                    array.push(Box::pin(self.require_value()).await?);
+                    array.push(Box::pin(self.require_element(totals)).await?);
                 }
                 RespValue::Array(array)
             }
@@ -134,40 +394,32 @@ impl<Inner: AsyncRead + Unpin> RespReader<Inner> {
                 #[allow(clippy::mutable_key_type)]
                 let mut map = BTreeMap::new();
                 for _ in 0..size {
-                    let key = Box::pin(self.require_value()).await?.try_into()?;
-                    let value = Box::pin(self.require_value()).await?;
+                    let key = Box::pin(self.require_key(totals)).await?;
+                    let value = Box::pin(self.require_element(totals)).await?;
                     if map.insert(key, value).is_some() {
                         return Err(RespError::InvalidMap);
                     }
                 }
                 RespValue::Attribute(map)
             }
-            Bignum(value) => RespValue::Bignum(value),
-            BlobError(value) => RespValue::Error(value),
-            Boolean(value) => value.into(),
-            BlobString(value) | SimpleString(value) => RespValue::String(value),
-            Double(value) => RespValue::Double(value),
-            SimpleError(value) => RespValue::Error(value),
-            Integer(i) => i.into(),
             Map(size) => {
                 // Bytes is a false positive here.
                 // <https://rust-lang.github.io/rust-clippy/master/index.html#mutable_key_type>
                 #[allow(clippy::mutable_key_type)]
                 let mut map = BTreeMap::new();
                 for _ in 0..size {
-                    let key = Box::pin(self.require_value()).await?.try_into()?;
-                    let value = Box::pin(self.require_value()).await?;
+                    let key = Box::pin(self.require_key(totals)).await?;
+                    let value = Box::pin(self.require_element(totals)).await?;
                     if map.insert(key, value).is_some() {
                         return Err(RespError::InvalidMap);
                     }
                 }
                 RespValue::Map(map)
             }
-            Nil => RespValue::Nil,
             Push(size) => {
                 let mut push = Vec::new();
                 for _ in 0..size {
-                    push.push(Box::pin(self.require_value()).await?);
+                    push.push(Box::pin(self.require_element(totals)).await?);
                 }
                 RespValue::Push(push)
             }
@@ -177,309 +429,654 @@ impl<Inner: AsyncRead + Unpin> RespReader<Inner> {
                 #[allow(clippy::mutable_key_type)]
                 let mut set = BTreeSet::new();
                 for _ in 0..size {
-                    let value = Box::pin(self.require_value()).await?.try_into()?;
+                    let value = Box::pin(self.require_key(totals)).await?;
                     if !set.insert(value) {
                         return Err(RespError::InvalidSet);
                     }
                 }
                 RespValue::Set(set)
             }
-            Verbatim(format, value) => RespValue::Verbatim(format, value),
+            frame => frame.try_into()?,
         };
 
         Ok(Some(result))
     }
 
-    /// Require one [`RespFrame`] from the stream.
-    async fn require_value(&mut self) -> Result<RespValue, RespError> {
-        self.value().await?.ok_or(RespError::EndOfInput)
-    }
-
-    /// Read the next [`RespFrame`] from the stream.
+    /// Read the next reply, setting aside any pushes received before it.
+    ///
+    /// Pushes are out of band, so skipping them keeps replies in the same order as requests.
+    /// They can be retrieved with [`RespReader::take_pushes`].
     ///
     /// ```
     /// # use tokio::runtime::Runtime;
-    /// # use respite::{RespConfig, RespFrame, RespReader};
+    /// # use respite::{RespConfig, RespValue, RespReader};
     /// # let runtime = Runtime::new().unwrap();
     /// # runtime.block_on(async {
-    /// let input = "$3\r\nhi!\r\n".as_bytes();
+    /// let input = ">2\r\n$7\r\nmessage\r\n$2\r\nhi\r\n:1\r\n".as_bytes();
     /// let mut reader = RespReader::new(input, RespConfig::default());
-    /// let frame = reader.frame().await.unwrap();
-    /// assert_eq!(frame, Some(RespFrame::BlobString("hi!".into())));
+    /// assert_eq!(reader.next_reply().await.unwrap(), Some(RespValue::Integer(1)));
+    /// assert_eq!(reader.take_pushes().len(), 1);
     /// # });
     /// ```
-    pub async fn frame(&mut self) -> Result<Option<RespFrame>, RespError> {
-        let Some(byte) = self.peek().await? else {
-            return Ok(None);
-        };
-
-        Ok(Some(match byte {
-            b'*' => self.read_array().await?,
-            b'(' => self.read_bignum().await?,
-            b'#' => self.read_boolean().await?,
-            b'$' => self.read_blob_string().await?,
-            b',' => self.read_double().await?,
-            b'-' => self.read_error().await?,
-            b':' => self.read_integer().await?,
-            b'%' => self.read_map().await?,
-            b'_' => self.read_nil().await?,
-            b'>' => self.read_push().await?,
-            b'~' => self.read_set().await?,
-            b'+' => self.read_simple_string().await?,
-            b'=' => self.read_verbatim().await?,
-            b'!' => self.read_blob_error().await?,
-            b'|' => self.read_attribute().await?,
-            c => return Err(RespError::UnknownType(c)),
-        }))
-    }
-
-    /// Read an array.
-    async fn read_array(&mut self) -> Result<RespFrame, RespError> {
-        self.require("*").await?;
-        if self.peek().await? == Some(b'-') {
-            self.require("-1\r\n").await?;
-            return Ok(RespFrame::Nil);
+    pub async fn next_reply(&mut self) -> Result<Option<RespValue>, RespError> {
+        loop {
+            match self.value_or_push().await? {
+                Some(RespMessage::Push(push)) => self.pushes.push(RespValue::Push(push)),
+                Some(RespMessage::Reply(value)) => return Ok(Some(value)),
+                None => return Ok(None),
+            }
         }
-        let size = self.read_size().await?;
-        Ok(RespFrame::Array(size))
-    }
-
-    /// Read a bignum.
-    async fn read_bignum(&mut self) -> Result<RespFrame, RespError> {
-        self.require("(").await?;
-        let value = self.read_line().await?;
-        Ok(RespFrame::Bignum(value))
     }
 
-    /// Read a boolean.
-    async fn read_boolean(&mut self) -> Result<RespFrame, RespError> {
-        self.require("#").await?;
-        let value = match self.pop().await? {
-            b't' => true,
-            b'f' => false,
-            _ => return Err(RespError::InvalidBoolean),
-        };
-        self.require("\r\n").await?;
-        Ok(RespFrame::Boolean(value))
+    /// Read the next [`RespValue`], separating pushes from replies.
+    ///
+    /// Only top-level pushes are separated. A push nested in a reply is part of that reply.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespMessage, RespValue, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = ">1\r\n$2\r\nhi\r\n:1\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let message = reader.value_or_push().await.unwrap();
+    /// assert_eq!(message, Some(RespMessage::Push(vec!["hi".into()])));
+    /// let message = reader.value_or_push().await.unwrap();
+    /// assert_eq!(message, Some(RespMessage::Reply(RespValue::Integer(1))));
+    /// # });
+    /// ```
+    pub async fn value_or_push(&mut self) -> Result<Option<RespMessage>, RespError> {
+        Ok(self.value().await?.map(RespMessage::from))
     }
 
-    /// Read a blob string.
-    async fn read_blob_string(&mut self) -> Result<RespFrame, RespError> {
-        self.require("$").await?;
-        if self.peek().await? == Some(b'-') {
-            self.require("-1\r\n").await?;
-            return Ok(RespFrame::Nil);
-        }
-        let size = self.read_size().await?;
-        if size > self.config.blob_limit() {
-            return Err(RespError::InvalidBlobLength);
-        }
-        let value = self.read_exact(size).await?;
-        self.require("\r\n").await?;
-        Ok(RespFrame::BlobString(value))
+    /// Take the pushes set aside by [`RespReader::next_reply`], in the order they were received.
+    pub fn take_pushes(&mut self) -> Vec<RespValue> {
+        mem::take(&mut self.pushes)
     }
 
-    /// Read a double.
-    async fn read_double(&mut self) -> Result<RespFrame, RespError> {
-        self.require(",").await?;
-        let value = self.read_line().await?;
-        let value = std::str::from_utf8(&value[..])
-            .ok()
-            .and_then(|x| x.parse().ok())
-            .ok_or(RespError::InvalidDouble)?;
-        Ok(RespFrame::Double(value))
+    /// Read the next [`RespValue`] from the stream, along with the [`position`] where it started.
+    ///
+    /// [`position`]: RespReader::position
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespValue, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = ":1\r\n$3\r\nhi!\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// assert_eq!(reader.value_with_offset().await.unwrap(), Some((0, RespValue::Integer(1))));
+    /// assert_eq!(reader.value_with_offset().await.unwrap(), Some((4, "hi!".into())));
+    /// # });
+    /// ```
+    pub async fn value_with_offset(&mut self) -> Result<Option<(usize, RespValue)>, RespError> {
+        let offset = self.position();
+        Ok(self.value().await?.map(|value| (offset, value)))
     }
 
-    /// Read an error.
-    async fn read_error(&mut self) -> Result<RespFrame, RespError> {
-        self.require("-").await?;
-        let value = self.read_line().await?;
-        Ok(RespFrame::SimpleError(value))
+    /// Read the next [`RespValue`] from the stream, along with any attributes preceding it.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespValue, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "|1\r\n+ttl\r\n:3\r\n+hi!\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let (attributes, value) = reader.value_with_attributes().await.unwrap().unwrap();
+    /// assert_eq!(attributes.unwrap().len(), 1);
+    /// assert_eq!(value, RespValue::SimpleString("hi!".into()));
+    /// # });
+    /// ```
+    pub async fn value_with_attributes(
+        &mut self,
+    ) -> Result<Option<(Option<BTreeMap<RespPrimitive, RespValue>>, RespValue)>, RespError> {
+        Ok(self
+            .value()
+            .await?
+            .map(|value| (self.attributes.clone(), value)))
     }
 
-    /// Read an integer.
-    async fn read_integer(&mut self) -> Result<RespFrame, RespError> {
-        self.require(":").await?;
-        let line = self.read_line().await?;
-        let value = std::str::from_utf8(&line[..])
-            .ok()
-            .and_then(|x| x.parse().ok())
-            .ok_or(RespError::InvalidInteger)?;
-        Ok(RespFrame::Integer(value))
+    /// Read the bytes of the next string frame, without building a [`RespValue`].
+    ///
+    /// Blob, simple, and verbatim strings are accepted. Any other frame is an error.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "$3\r\nhi!\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let bytes = reader.read_scalar_bytes().await.unwrap();
+    /// assert_eq!(bytes, Some("hi!".into()));
+    /// # });
+    /// ```
+    pub async fn read_scalar_bytes(&mut self) -> Result<Option<Bytes>, RespError> {
+        use RespFrame::*;
+        match self.frame().await? {
+            Some(BlobString(value) | SimpleString(value) | Verbatim(_, value)) => Ok(Some(value)),
+            Some(_) => Err(RespError::ExpectedString),
+            None => Ok(None),
+        }
     }
 
-    /// Read a map.
-    async fn read_map(&mut self) -> Result<RespFrame, RespError> {
-        self.require("%").await?;
-        let size = self.read_size().await?;
-        Ok(RespFrame::Map(size))
-    }
+    /// Read an array of exactly `expected` values.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespValue, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "*2\r\n:1\r\n:2\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let array = reader.array_of(2).await.unwrap();
+    /// assert_eq!(array, vec![RespValue::Integer(1), RespValue::Integer(2)]);
+    /// # });
+    /// ```
+    pub async fn array_of(&mut self, expected: usize) -> Result<Vec<RespValue>, RespError> {
+        let got = match self.frame().await? {
+            Some(RespFrame::Array(size)) => size,
+            Some(_) => return Err(RespError::ExpectedArray),
+            None => return Err(RespError::EndOfInput),
+        };
 
-    /// Read a nil.
-    async fn read_nil(&mut self) -> Result<RespFrame, RespError> {
-        self.require("_\r\n").await?;
-        Ok(RespFrame::Nil)
-    }
+        if got != expected {
+            return Err(RespError::UnexpectedArity { expected, got });
+        }
 
-    /// Read a push.
-    async fn read_push(&mut self) -> Result<RespFrame, RespError> {
-        self.require(">").await?;
-        let size = self.read_size().await?;
-        Ok(RespFrame::Push(size))
+        let mut totals = ValueTotals::default();
+        self.add_elements(&mut totals, got)?;
+        let mut array = Vec::new();
+        for _ in 0..got {
+            array.push(self.require_value(&mut totals).await?);
+        }
+        Ok(array)
     }
 
-    /// Read a set.
-    async fn read_set(&mut self) -> Result<RespFrame, RespError> {
-        self.require("~").await?;
-        let size = self.read_size().await?;
-        Ok(RespFrame::Set(size))
+    /// Read a scan reply, returning the cursor and the elements.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespValue, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "*2\r\n$1\r\n0\r\n*1\r\n$1\r\na\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let (cursor, elements) = reader.read_scan().await.unwrap();
+    /// assert_eq!(cursor, "0");
+    /// assert_eq!(elements, vec![RespValue::String("a".into())]);
+    /// # });
+    /// ```
+    pub async fn read_scan(&mut self) -> Result<(Bytes, Vec<RespValue>), RespError> {
+        let reply = <[RespValue; 2]>::try_from(self.array_of(2).await?);
+        match reply {
+            Ok([RespValue::String(cursor), RespValue::Array(elements)]) => Ok((cursor, elements)),
+            _ => Err(RespError::InvalidReply),
+        }
     }
 
-    /// Read a simple string.
-    async fn read_simple_string(&mut self) -> Result<RespFrame, RespError> {
-        self.require("+").await?;
-        let value = self.read_line().await?;
-        Ok(RespFrame::SimpleString(value))
-    }
+    /// Read an array, converting each element with `parse`.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespError, RespValue, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "*2\r\n:1\r\n:2\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let array = reader
+    ///     .read_array_of(|value| value.integer().ok_or(RespError::InvalidReply))
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(array, vec![1, 2]);
+    /// # });
+    /// ```
+    pub async fn read_array_of<T>(
+        &mut self,
+        parse: impl Fn(RespValue) -> Result<T, RespError>,
+    ) -> Result<Vec<T>, RespError> {
+        let size = match self.frame().await? {
+            Some(RespFrame::Array(size)) => size,
+            Some(_) => return Err(RespError::ExpectedArray),
+            None => return Err(RespError::EndOfInput),
+        };
 
-    /// Read a verbatim.
-    async fn read_verbatim(&mut self) -> Result<RespFrame, RespError> {
-        self.require("=").await?;
-        let size = self.read_size().await?;
-        if size > self.config.blob_limit() {
-            return Err(RespError::InvalidBlobLength);
-        }
-        if size < 4 {
-            return Err(RespError::InvalidVerbatim);
-        }
-        let value = self.read_exact(size).await?;
-        if value.get(3) != Some(&b':') {
-            return Err(RespError::InvalidVerbatim);
-        }
-        let format = value.slice(..3);
-        let value = value.slice(4..);
-        self.require("\r\n").await?;
-        Ok(RespFrame::Verbatim(format, value))
-    }
-
-    /// Read a blob error.
-    async fn read_blob_error(&mut self) -> Result<RespFrame, RespError> {
-        self.require("!").await?;
-        let size = self.read_size().await?;
-        if size > self.config.blob_limit() {
-            return Err(RespError::InvalidBlobLength);
+        let mut totals = ValueTotals::default();
+        self.add_elements(&mut totals, size)?;
+        let mut array = Vec::new();
+        for _ in 0..size {
+            array.push(parse(self.require_value(&mut totals).await?)?);
         }
-        let value = self.read_exact(size).await?;
-        self.require("\r\n").await?;
-        Ok(RespFrame::BlobError(value))
+        Ok(array)
     }
 
-    /// Read an attribute.
-    async fn read_attribute(&mut self) -> Result<RespFrame, RespError> {
-        self.require("|").await?;
-        let size = self.read_size().await?;
-        Ok(RespFrame::Attribute(size))
-    }
+    /// Read `n` subscribe confirmations, returning the channel and subscription count of each.
+    ///
+    /// Each confirmation must be an array or push of `subscribe`, a channel, and a count.
+    pub async fn read_subscribe_confirms(
+        &mut self,
+        n: usize,
+    ) -> Result<Vec<(Bytes, i64)>, RespError> {
+        let mut totals = ValueTotals::default();
+        let mut confirms = Vec::new();
+        for _ in 0..n {
+            let (RespValue::Array(value) | RespValue::Push(value)) =
+                self.require_value(&mut totals).await?
+            else {
+                return Err(RespError::InvalidReply);
+            };
+
+            let [RespValue::String(kind), RespValue::String(channel), RespValue::Integer(count)] =
+                &value[..]
+            else {
+                return Err(RespError::InvalidReply);
+            };
+
+            if kind != "subscribe" {
+                return Err(RespError::InvalidReply);
+            }
 
-    /// Try to read some data from `inner`.
-    async fn read(&mut self) -> Result<usize, RespError> {
-        Ok(self.inner.read_buf(&mut self.buffer).await?)
+            confirms.push((channel.clone(), *count));
+        }
+        Ok(confirms)
     }
 
-    /// Read one byte.
-    async fn pop(&mut self) -> Result<u8, RespError> {
-        if self.buffer.is_empty() {
-            self.read_some().await?;
+    /// Read the reply to `EXEC`, splitting each result into a value or an error message.
+    ///
+    /// Returns `None` if the transaction was aborted.
+    pub async fn read_exec(&mut self) -> Result<Option<Vec<Result<RespValue, Bytes>>>, RespError> {
+        match self.require_value(&mut ValueTotals::default()).await? {
+            RespValue::Array(values) => Ok(Some(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        RespValue::Error(message) => Err(message),
+                        value => Ok(value),
+                    })
+                    .collect(),
+            )),
+            RespValue::Nil => Ok(None),
+            _ => Err(RespError::InvalidReply),
         }
-        Ok(self.buffer.get_u8())
     }
 
-    /// Try to read some data from `inner`. Return an error if we've reached the end of the input.
-    async fn read_some(&mut self) -> Result<(), RespError> {
-        if self.read().await? == 0 {
-            return Err(RespError::EndOfInput);
+    /// Add `count` declared elements to the running `totals`, checking the limit.
+    fn add_elements(&self, totals: &mut ValueTotals, count: usize) -> Result<(), RespError> {
+        totals.elements = totals.elements.saturating_add(count);
+        if totals.elements > self.config.total_elements_limit() {
+            return Err(RespError::TooManyElements);
         }
-
         Ok(())
     }
 
-    /// Read a size.
-    async fn read_size(&mut self) -> Result<usize, RespError> {
-        let mut size = 0;
-
-        if self.peek().await? == Some(b'\r') {
-            return Err(RespError::InvalidBlobLength);
-        }
-
+    /// Require one value, skipping any attributes preceding it and adding to the running `totals`.
+    ///
+    /// Sharing `totals` across several values applies the limits to all of them together.
+    async fn require_value(&mut self, totals: &mut ValueTotals) -> Result<RespValue, RespError> {
         loop {
-            match self.pop().await? {
-                b'\r' => {
-                    self.require("\n").await?;
-                    return Ok(size);
-                }
-                b @ b'0'..=b'9' => {
-                    let n = (b - b'0').into();
-                    size = size
-                        .checked_mul(10)
-                        .and_then(|size| size.checked_add(n))
-                        .ok_or(RespError::InvalidBlobLength)?;
-                }
-                _ => return Err(RespError::InvalidBlobLength),
+            match self.read_value(totals).await? {
+                Some(RespValue::Attribute(_)) => continue,
+                Some(value) => return Ok(value),
+                None => return Err(RespError::EndOfInput),
             }
         }
     }
 
-    /// Require a specific sequence of bytes and consume them.
-    async fn require<E>(&mut self, expected: E) -> Result<(), RespError>
-    where
-        E: AsRef<[u8]> + Send + Sync,
-    {
-        for expected in expected.as_ref() {
-            let got = self.pop().await?;
-
-            if got != *expected {
-                return Err(RespError::Unexpected(*expected, got));
+    /// Require one element of an aggregate, adding to the running `totals`.
+    async fn require_element(&mut self, totals: &mut ValueTotals) -> Result<RespValue, RespError> {
+        match self.read_value(totals).await? {
+            Some(RespValue::Error(error)) if self.config.fail_on_nested_error() => {
+                Err(RespError::Server(String::from_utf8_lossy(&error).into()))
             }
+            Some(value) => Ok(value),
+            None => Err(RespError::EndOfInput),
         }
-
-        Ok(())
     }
 
-    /// Read an entire line.
-    async fn read_line(&mut self) -> Result<Bytes, RespError> {
-        let mut from = 0;
-        let slice = loop {
-            let to = cmp::min(self.config.inline_limit(), self.buffer.len());
-            let index = self.buffer[from..to].iter().position(|&b| b == b'\r');
-
-            if let Some(index) = index {
-                break self.buffer.split_to(from + index);
-            }
-
-            if self.buffer.len() > self.config.inline_limit() {
-                return Err(RespError::TooBigInline);
+    /// Read a map key or set member, skipping any attribute preceding it.
+    async fn require_key(&mut self, totals: &mut ValueTotals) -> Result<RespPrimitive, RespError> {
+        loop {
+            match self.require_element(totals).await? {
+                RespValue::Attribute(_) => continue,
+                value => return value.try_into(),
             }
-
-            from = self.buffer.len();
-            self.read_some().await?;
-        };
-
-        self.require("\r\n").await?;
-        Ok(slice.freeze())
+        }
+    }
+
+    /// Read the next [`RespFrame`] from the stream.
+    ///
+    /// Returns `Ok(None)` only when the stream has ended cleanly between frames. If the stream is
+    /// still open but has no data available, such as an idle pipe or interactive input, this waits
+    /// for more data instead. A stream that ends partway through a frame returns
+    /// [`RespError::EndOfInput`].
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespFrame, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "$3\r\nhi!\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let frame = reader.frame().await.unwrap();
+    /// assert_eq!(frame, Some(RespFrame::BlobString("hi!".into())));
+    /// # });
+    /// ```
+    pub async fn frame(&mut self) -> Result<Option<RespFrame>, RespError> {
+        let Some((frame, len)) = self.parse().await? else {
+            return Ok(None);
+        };
+        Ok(Some(frame.into_frame(&self.buffer.split_to(len).freeze())))
+    }
+
+    /// Skip input through the next `\r\n`, returning the number of bytes skipped.
+    ///
+    /// This is meant for best-effort recovery after an error like [`RespError::UnknownType`],
+    /// by skipping to a plausible frame boundary. If the stream ends first, everything is skipped.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespError, RespFrame, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "?garbage\r\n:1\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// assert!(matches!(reader.frame().await, Err(RespError::UnknownType(b'?'))));
+    /// assert_eq!(reader.resync().await.unwrap(), 10);
+    /// assert_eq!(reader.frame().await.unwrap(), Some(RespFrame::Integer(1)));
+    /// # });
+    /// ```
+    pub async fn resync(&mut self) -> Result<usize, RespError> {
+        self.consume();
+        self.parser.reset();
+        let mut skipped = 0;
+        loop {
+            if let Some(index) = memchr::memmem::find(&self.buffer, b"\r\n") {
+                self.buffer.advance(index + 2);
+                return Ok(skipped + index + 2);
+            }
+
+            // Keep a trailing `\r`, in case the `\n` hasn't been read yet.
+            let keep = usize::from(self.buffer.last() == Some(&b'\r'));
+            let n = self.buffer.len() - keep;
+            self.buffer.advance(n);
+            skipped += n;
+
+            if self.read(1).await? == 0 {
+                skipped += self.buffer.len();
+                self.buffer.clear();
+                return Ok(skipped);
+            }
+        }
+    }
+
+    /// Read the next frame, borrowing its data from the reader's buffer.
+    ///
+    /// The frame stays in the buffer until the next read, so forwarding it doesn't copy its data
+    /// or keep the buffer's allocation alive.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespFrameRef, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "$3\r\nhi!\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// let frame = reader.frame_ref().await.unwrap();
+    /// assert_eq!(frame, Some(RespFrameRef::BlobString(b"hi!")));
+    /// # });
+    /// ```
+    pub async fn frame_ref(&mut self) -> Result<Option<RespFrameRef<'_>>, RespError> {
+        let Some((frame, len)) = self.parse().await? else {
+            return Ok(None);
+        };
+        self.borrowed = len;
+        Ok(Some(frame.as_frame_ref(&self.buffer)))
+    }
+
+    /// Remove the frame last borrowed with [`RespReader::frame_ref`] from the buffer.
+    fn consume(&mut self) {
+        self.buffer.advance(mem::take(&mut self.borrowed));
+    }
+
+    /// Require that the stream has ended, with no unread data remaining.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "+OK\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// reader.frame().await.unwrap();
+    /// reader.finish().await.unwrap();
+    /// # });
+    /// ```
+    pub async fn finish(&mut self) -> Result<(), RespError> {
+        match self.peek().await? {
+            Some(_) => Err(RespError::TrailingData),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse the frame at the start of the buffer, reading until it's whole.
+    ///
+    /// The frame is left in the buffer, and returned with its length. Returns `None` if the
+    /// stream ended cleanly before another frame started.
+    async fn parse(&mut self) -> Result<Option<(Parsed, usize)>, RespError> {
+        self.consume();
+        loop {
+            if let Some(parsed) = self
+                .parser
+                .parse(&self.buffer, &self.config, self.version)?
+            {
+                self.frames += 1;
+                return Ok(Some(parsed));
+            }
+
+            // Reserve space for the rest of a blob, without trusting its declared size.
+            let want = self.parser.wanted(&self.buffer);
+            if want > 1 {
+                self.buffer.reserve(cmp::min(want, READ_RESERVE_LIMIT));
+            }
+
+            if self.read(want).await? == 0 {
+                self.parser.reset();
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                return Err(RespError::EndOfInput);
+            }
+        }
+    }
+
+    /// Try to read some data from `inner`, needing at least `want` more bytes to make progress.
+    ///
+    /// Reads stop short of [`RespConfig::read_high_water`] unless more is needed, so that
+    /// pipelined input stays in `inner` until the buffered input has been processed. Once more
+    /// is needed, at least [`READ_MIN_CHUNK`] is read, so that a long frame doesn't take a read
+    /// per byte.
+    async fn read(&mut self, want: usize) -> Result<usize, RespError> {
+        let room = self
+            .config
+            .read_high_water()
+            .saturating_sub(self.buffer.len());
+        let limit = if want > room {
+            let chunk = want.clamp(READ_MIN_CHUNK, READ_RESERVE_LIMIT);
+            self.buffer.reserve(chunk);
+            chunk
+        } else {
+            room
+        } as u64;
+        let n = (&mut self.inner)
+            .take(limit)
+            .read_buf(&mut self.buffer)
+            .await?;
+        self.received += n;
+        Ok(n)
+    }
+
+    /// Read one byte.
+    async fn pop(&mut self) -> Result<u8, RespError> {
+        self.consume();
+        if self.buffer.is_empty() {
+            self.read_some(1).await?;
+        }
+        Ok(self.buffer.get_u8())
+    }
+
+    /// Try to read some data from `inner`. Return an error if we've reached the end of the input.
+    async fn read_some(&mut self, want: usize) -> Result<(), RespError> {
+        if self.read(want).await? == 0 {
+            return Err(RespError::EndOfInput);
+        }
+
+        Ok(())
+    }
+
+    /// Read a size.
+    ///
+    /// Sizes longer than [`SIZE_DIGITS_LIMIT`] are rejected early, even if they're all zeros.
+    async fn read_size(&mut self) -> Result<usize, RespError> {
+        let size = self.read_size_u64(b'*').await?;
+        usize::try_from(size).map_err(|_| RespError::InvalidLength)
+    }
+
+    /// Read the size of a frame of type `kind` as a [`u64`], so that overflow doesn't depend on
+    /// the width of [`usize`].
+    async fn read_size_u64(&mut self, kind: u8) -> Result<u64, RespError> {
+        let mut size: u64 = 0;
+
+        if self.peek().await? == Some(b'\r') {
+            return Err(invalid_size(kind));
+        }
+
+        for _ in 0..=SIZE_DIGITS_LIMIT {
+            match self.pop().await? {
+                b'\r' => {
+                    self.require("\n").await?;
+                    return Ok(size);
+                }
+                b @ b'0'..=b'9' => {
+                    let n = (b - b'0').into();
+                    size = size
+                        .checked_mul(10)
+                        .and_then(|size| size.checked_add(n))
+                        .ok_or_else(|| too_big_size(kind))?;
+                }
+                _ => return Err(invalid_size(kind)),
+            }
+        }
+
+        Err(too_big_size(kind))
+    }
+
+    /// Read the size of a blob, checking it against [`RespConfig::blob_limit`].
+    ///
+    /// The size is checked against the limit before converting it to a [`usize`], so that sizes
+    /// over the limit fail the same way on every platform.
+    async fn read_blob_size(&mut self) -> Result<usize, RespError> {
+        let size = self.read_size_u64(b'$').await?;
+        if size > self.config.blob_limit() as u64 {
+            return Err(RespError::TooBigBlob);
+        }
+        usize::try_from(size).map_err(|_| RespError::TooBigBlob)
+    }
+
+    /// Require a specific sequence of bytes and consume them.
+    async fn require<E>(&mut self, expected: E) -> Result<(), RespError>
+    where
+        E: AsRef<[u8]> + Send + Sync,
+    {
+        for expected in expected.as_ref() {
+            let got = self.pop().await?;
+
+            if got != *expected {
+                return Err(RespError::Unexpected(*expected, got));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read an entire line, not including the trailing `\r\n`.
+    ///
+    /// This is useful for extending the protocol with custom line-based frames. Lines longer than
+    /// [`RespConfig::inline_limit`] return [`RespError::TooBigInline`].
+    ///
+    /// The buffer always starts at the beginning of the line, so the inline limit applies to the
+    /// current line only, and not to any pipelined data following it.
+    ///
+    /// Lines end with `\r\n`, or also with a bare `\n` if [`RespConfig::lenient_newlines`] is set.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespConfig, RespReader};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let input = "@custom\r\n".as_bytes();
+    /// let mut reader = RespReader::new(input, RespConfig::default());
+    /// assert_eq!(reader.read_line().await.unwrap(), "@custom");
+    /// # });
+    /// ```
+    pub async fn read_line(&mut self) -> Result<Bytes, RespError> {
+        self.consume();
+
+        // Read the limit once, so that each byte is only scanned once even if it changes.
+        let limit = self.config.inline_limit();
+        let lenient = self.config.lenient_newlines();
+        let mut from = 0;
+        let slice = loop {
+            // A line of exactly `limit` bytes is allowed, so its `\r` may be just past the limit.
+            let to = cmp::min(limit.saturating_add(1), self.buffer.len());
+            let index = if lenient {
+                memchr::memchr2(b'\r', b'\n', &self.buffer[from..to])
+            } else {
+                memchr::memchr(b'\r', &self.buffer[from..to])
+            };
+            #[cfg(test)]
+            {
+                self.scanned += to - from;
+            }
+
+            if let Some(index) = index {
+                break self.buffer.split_to(from + index);
+            }
+
+            if self.buffer.len() > limit {
+                return Err(RespError::TooBigInline);
+            }
+
+            from = to;
+            self.read_some(1).await?;
+        };
+
+        if lenient && self.buffer.first() == Some(&b'\n') {
+            self.buffer.advance(1);
+        } else {
+            self.require("\r\n").await?;
+        }
+        Ok(slice.freeze())
     }
 
     /// Read an exact number of bytes.
+    ///
+    /// If the bytes are already buffered, they're split off without reserving more space.
+    /// Otherwise, space is reserved for the remaining bytes, up to [`READ_RESERVE_LIMIT`] at a
+    /// time, so that large blobs are read in large chunks without trusting the declared size.
     async fn read_exact(&mut self, len: usize) -> Result<Bytes, RespError> {
-        self.buffer.reserve(len);
         while self.buffer.len() < len {
-            self.read_some().await?;
+            let remaining = len - self.buffer.len();
+            self.buffer.reserve(cmp::min(remaining, READ_RESERVE_LIMIT));
+            self.read_some(remaining).await?;
         }
         Ok(self.buffer.split_to(len).freeze())
     }
 
     /// Peek at the next byte in the stream.
     async fn peek(&mut self) -> Result<Option<u8>, RespError> {
-        if self.buffer.is_empty() && self.read().await? == 0 {
+        self.consume();
+        if self.buffer.is_empty() && self.read(1).await? == 0 {
             return Ok(None);
         }
 
@@ -487,11 +1084,126 @@ impl<Inner: AsyncRead + Unpin> RespReader<Inner> {
     }
 }
 
+impl RespReader<Empty> {
+    /// Create a [`RespReader`] over the bytes already in `buf`.
+    ///
+    /// Reading never waits, so frames and values can be read synchronously with
+    /// [`RespReader::frame_sync`] and [`RespReader::value_sync`]. Once `buf` is exhausted, the
+    /// stream ends.
+    ///
+    /// ```
+    /// # use bytes::Bytes;
+    /// # use respite::{RespConfig, RespFrame, RespReader};
+    /// let input = Bytes::from_static(b"+OK\r\n:1\r\n");
+    /// let mut reader = RespReader::from_buf(input, RespConfig::default());
+    /// assert_eq!(reader.frame_sync().unwrap(), Some(RespFrame::SimpleString("OK".into())));
+    /// assert_eq!(reader.frame_sync().unwrap(), Some(RespFrame::Integer(1)));
+    /// assert_eq!(reader.frame_sync().unwrap(), None);
+    /// ```
+    pub fn from_buf<B: Buf>(buf: B, config: RespConfig) -> Self {
+        let mut reader = Self::new(tokio::io::empty(), config);
+        reader.buffer.put(buf);
+        reader.received = reader.buffer.len();
+        reader
+    }
+
+    /// Read the next [`RespFrame`] synchronously. See [`RespReader::frame`].
+    pub fn frame_sync(&mut self) -> Result<Option<RespFrame>, RespError> {
+        ready(self.frame())
+    }
+
+    /// Read the next [`RespValue`] synchronously. See [`RespReader::value`].
+    pub fn value_sync(&mut self) -> Result<Option<RespValue>, RespError> {
+        ready(self.value())
+    }
+}
+
+/// A future that is pending once, to let other tasks run.
+#[derive(Default)]
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        context.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Check that `input` is exactly one well-formed [`RespFrame`], returning its length.
+///
+/// This is useful for validating bytes before forwarding them.
+///
+/// ```
+/// # use respite::{validate_frame, RespConfig, RespError};
+/// let config = RespConfig::default();
+/// assert_eq!(validate_frame(b"$3\r\nhi!\r\n", &config).unwrap(), 9);
+/// assert!(matches!(validate_frame(b"$3\r\nhi", &config), Err(RespError::EndOfInput)));
+/// ```
+pub fn validate_frame(input: &[u8], config: &RespConfig) -> Result<usize, RespError> {
+    let (_, len) = parse_start(input, config)?;
+    if len != input.len() {
+        return Err(RespError::TrailingData);
+    }
+    Ok(len)
+}
+
+/// Parse one [`RespFrame`] from the start of `input`, returning it with the number of bytes it
+/// used.
+///
+/// Returns [`RespError::EndOfInput`] if `input` doesn't contain a whole frame.
+///
+/// ```
+/// # use respite::{parse_frame, RespConfig, RespError, RespFrame};
+/// let config = RespConfig::default();
+/// let (frame, len) = parse_frame(b"$3\r\nhi!\r\n:1\r\n", &config).unwrap();
+/// assert_eq!(frame, RespFrame::BlobString("hi!".into()));
+/// assert_eq!(len, 9);
+/// assert!(matches!(parse_frame(b"$3\r\nhi", &config), Err(RespError::EndOfInput)));
+/// ```
+pub fn parse_frame(input: &[u8], config: &RespConfig) -> Result<(RespFrame, usize), RespError> {
+    let (parsed, len) = parse_start(input, config)?;
+    let frame = parsed.into_frame(&Bytes::copy_from_slice(&input[..len]));
+    Ok((frame, len))
+}
+
+/// Parse the frame at the start of `input`, which must contain all of it.
+fn parse_start(input: &[u8], config: &RespConfig) -> Result<(Parsed, usize), RespError> {
+    FrameParser::default()
+        .parse(input, config, RespVersion::V2)?
+        .ok_or(RespError::EndOfInput)
+}
+
+/// A waker that does nothing, for polling futures that are always ready.
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Run a future that never waits, such as reading from a slice or buffer.
+pub(crate) fn ready<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    match pin!(future).poll(&mut context) {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!("reading from a slice or buffer never waits"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use bytes::Bytes;
     use std::collections::VecDeque;
+    use tokio::io::AsyncWriteExt;
 
     macro_rules! assert_frame {
         ($input:expr, $expected:expr) => {{
@@ -544,12 +1256,58 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn frame_eof_vs_pending() -> Result<(), RespError> {
+        let (mut client, server) = tokio::io::duplex(64);
+        let mut reader = RespReader::new(server, RespConfig::default());
+
+        tokio::select! {
+            biased;
+            _ = reader.frame() => panic!("frame should be pending on an open stream"),
+            _ = tokio::task::yield_now() => {}
+        }
+
+        client.write_all(b"+OK\r\n").await?;
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::SimpleString("OK".into()))
+        );
+
+        drop(client);
+        assert_eq!(reader.frame().await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn finish() -> Result<(), RespError> {
+        let mut reader = RespReader::new("+OK\r\n:1\r\n".as_bytes(), RespConfig::default());
+        reader.frame().await?;
+        assert!(matches!(
+            reader.finish().await,
+            Err(RespError::TrailingData)
+        ));
+        reader.frame().await?;
+        reader.finish().await?;
+
+        let (mut client, server) = tokio::io::duplex(64);
+        let mut reader = RespReader::new(server, RespConfig::default());
+        client.write_all(b"+OK\r\n").await?;
+        reader.frame().await?;
+        client.write_all(b"garbage").await?;
+        drop(client);
+        assert!(matches!(
+            reader.finish().await,
+            Err(RespError::TrailingData)
+        ));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn array_frame() -> Result<(), RespError> {
         assert_frame!("*0\r\n", RespFrame::Array(0));
         assert_frame!("*1\r\n", RespFrame::Array(1));
         assert_frame!("*-1\r\n", RespFrame::Nil);
-        assert_frame_error!("*\r\n", RespError::InvalidBlobLength);
+        assert_frame_error!("*\r\n", RespError::InvalidLength);
         assert_frame_error!("*1", RespError::EndOfInput);
         Ok(())
     }
@@ -561,6 +1319,20 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn bignum_limit() -> Result<(), RespError> {
+        let config = RespConfig::builder().bignum_limit(3).build();
+        let mut reader = RespReader::new("(123\r\n(-123\r\n".as_bytes(), config.clone());
+        assert_eq!(reader.frame().await?, Some(RespFrame::Bignum("123".into())));
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::Bignum("-123".into()))
+        );
+        assert_frame_error!("(1234\r\n", RespError::TooBigBignum, config.clone());
+        assert_frame_error!("(-1234\r\n", RespError::TooBigBignum, config);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn boolean_frame() -> Result<(), RespError> {
         assert_frame!("#t\r\n", RespFrame::Boolean(true));
@@ -571,6 +1343,168 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn value_with_offset() -> Result<(), RespError> {
+        let input = "*2\r\n:1\r\n+a\r\n$3\r\nabc\r\n";
+        let input = Trickle {
+            input: input.as_bytes(),
+            chunk: 5,
+            reads: 0,
+        };
+        let len = input.input.len();
+        let mut reader = RespReader::new(input, RespConfig::default());
+        assert_eq!(reader.position(), 0);
+        assert_eq!(
+            reader.value_with_offset().await?,
+            Some((0, resp! { [1, (+ "a")] }))
+        );
+        assert_eq!(reader.position(), 12);
+        assert_eq!(
+            reader.value_with_offset().await?,
+            Some((12, resp! { "abc" }))
+        );
+        assert_eq!(reader.position(), len);
+        assert_eq!(reader.value_with_offset().await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn total_limit() -> Result<(), RespError> {
+        let input = "*3\r\n$4\r\nabcd\r\n+efgh\r\n=7\r\ntxt:ijk\r\n";
+        let config = RespConfig::builder().total_limit(14).build();
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert!(reader.value().await?.is_some());
+
+        let config = RespConfig::builder().total_limit(13).build();
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert!(matches!(reader.value().await, Err(RespError::TooLarge)));
+
+        // The limit applies to each top-level value separately.
+        let input = "$4\r\nabcd\r\n$4\r\nefgh\r\n";
+        let config = RespConfig::builder().total_limit(4).build();
+        let mut reader = RespReader::new(input.as_bytes(), config.clone());
+        assert_eq!(reader.value().await?, Some(resp! { "abcd" }));
+        assert_eq!(reader.value().await?, Some(resp! { "efgh" }));
+
+        // Helpers that read several values apply the limit to all of them together.
+        let input = "*2\r\n$4\r\nabcd\r\n$4\r\nefgh\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), config.clone());
+        assert!(matches!(reader.array_of(2).await, Err(RespError::TooLarge)));
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert!(matches!(
+            reader.read_array_of(Ok).await,
+            Err(RespError::TooLarge)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn value_or_push() -> Result<(), RespError> {
+        let input = "*1\r\n>1\r\n:1\r\n>2\r\n$7\r\nmessage\r\n$1\r\na\r\n+OK\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.value_or_push().await?,
+            Some(RespMessage::Reply(resp! { [[> 1]] }))
+        );
+        assert_eq!(
+            reader.value_or_push().await?,
+            Some(RespMessage::Push(vec![resp! { "message" }, resp! { "a" }]))
+        );
+        assert_eq!(
+            reader.value_or_push().await?,
+            Some(RespMessage::Reply(resp! { (+ "OK") }))
+        );
+        assert_eq!(reader.value_or_push().await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn next_reply() -> Result<(), RespError> {
+        let input = concat!(
+            ">2\r\n$7\r\nmessage\r\n$1\r\na\r\n",
+            "+OK\r\n",
+            ">2\r\n$7\r\nmessage\r\n$1\r\nb\r\n",
+            ">2\r\n$7\r\nmessage\r\n$1\r\nc\r\n",
+            ":2\r\n",
+            "*1\r\n:3\r\n",
+            ">2\r\n$7\r\nmessage\r\n$1\r\nd\r\n",
+        );
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.next_reply().await?, Some(resp! { (+ "OK") }));
+        assert_eq!(reader.take_pushes(), vec![resp! { [> "message", "a"] }]);
+        assert_eq!(reader.next_reply().await?, Some(resp! { 2 }));
+        assert_eq!(reader.next_reply().await?, Some(resp! { [3] }));
+        assert_eq!(reader.next_reply().await?, None);
+        assert_eq!(
+            reader.take_pushes(),
+            vec![
+                resp! { [> "message", "b"] },
+                resp! { [> "message", "c"] },
+                resp! { [> "message", "d"] },
+            ]
+        );
+        assert_eq!(reader.take_pushes(), vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_buf() -> Result<(), RespError> {
+        let input = Bytes::from_static(b"*2\r\n:1\r\n$1\r\na\r\n#t\r\n+OK\r\n");
+        let mut reader = RespReader::from_buf(input, RespConfig::default());
+        assert_eq!(reader.frame_sync()?, Some(RespFrame::Array(2)));
+        assert_eq!(reader.frame_sync()?, Some(RespFrame::Integer(1)));
+        assert_eq!(
+            reader.frame_sync()?,
+            Some(RespFrame::BlobString("a".into()))
+        );
+        assert_eq!(reader.value_sync()?, Some(RespValue::Boolean(true)));
+        assert_eq!(reader.position(), 19);
+        assert_eq!(reader.value_sync()?, Some(resp! { (+ "OK") }));
+        assert_eq!(reader.frame_sync()?, None);
+
+        let input = Bytes::from_static(b":1\r\n$3\r\nab");
+        let mut reader = RespReader::from_buf(input.chain(&b""[..]), RespConfig::default());
+        assert_eq!(reader.frame_sync()?, Some(RespFrame::Integer(1)));
+        assert!(matches!(reader.frame_sync(), Err(RespError::EndOfInput)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unknown_types() -> Result<(), RespError> {
+        assert_frame_error!("@x\r\n", RespError::UnknownType(b'@'));
+
+        let config = RespConfig::builder().unknown_types(true).build();
+        let input = "@x y\r\n*2\r\n:1\r\n^\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), config.clone());
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::Unknown(b'@', "x y".into()))
+        );
+        assert_eq!(
+            reader.value().await?,
+            Some(RespValue::Array(vec![
+                RespValue::Integer(1),
+                RespValue::Unknown(b'^', "".into()),
+            ]))
+        );
+        assert_frame_error!("@x", RespError::EndOfInput, config);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lenient_booleans() -> Result<(), RespError> {
+        assert_frame_error!("#T\r\n", RespError::InvalidBoolean);
+        assert_frame_error!("#F\r\n", RespError::InvalidBoolean);
+
+        let config = RespConfig::builder().lenient_booleans(true).build();
+        let mut reader = RespReader::new("#T\r\n#F\r\n#t\r\n".as_bytes(), config.clone());
+        assert_eq!(reader.frame().await?, Some(RespFrame::Boolean(true)));
+        assert_eq!(reader.frame().await?, Some(RespFrame::Boolean(false)));
+        assert_eq!(reader.frame().await?, Some(RespFrame::Boolean(true)));
+        assert_frame_error!("#X\r\n", RespError::InvalidBoolean, config);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn blob_string_frame() -> Result<(), RespError> {
         assert_frame!("$5\r\nabcde\r\n", RespFrame::BlobString("abcde".into()));
@@ -580,11 +1514,7 @@ mod tests {
         assert_frame_error!("$\r\n\r\n", RespError::InvalidBlobLength);
         let mut config = RespConfig::default();
         config.set_blob_limit(5);
-        assert_frame_error!(
-            "$10\r\n1234567890\r\n",
-            RespError::InvalidBlobLength,
-            config
-        );
+        assert_frame_error!("$10\r\n1234567890\r\n", RespError::TooBigBlob, config);
         Ok(())
     }
 
@@ -600,6 +1530,13 @@ mod tests {
         assert_frame!(",inf\r\n", RespFrame::Double(f64::INFINITY.into()));
         assert_frame!(",-inf\r\n", RespFrame::Double(f64::NEG_INFINITY.into()));
         assert_frame!(",nan\r\n", RespFrame::Double(f64::NAN.into()));
+        assert_frame!(",+inf\r\n", RespFrame::Double(f64::INFINITY.into()));
+        assert_frame!(",Inf\r\n", RespFrame::Double(f64::INFINITY.into()));
+        assert_frame!(",-INF\r\n", RespFrame::Double(f64::NEG_INFINITY.into()));
+        assert_frame!(",NAN\r\n", RespFrame::Double(f64::NAN.into()));
+        assert_frame!(",NaN\r\n", RespFrame::Double(f64::NAN.into()));
+        assert_frame!(",+5\r\n", RespFrame::Double(5f64.into()));
+        assert_frame!(",-5\r\n", RespFrame::Double((-5f64).into()));
         assert_frame_error!(",invalid\r\n", RespError::InvalidDouble);
         assert_frame_error!(",5.4", RespError::EndOfInput);
         Ok(())
@@ -624,7 +1561,7 @@ mod tests {
     #[tokio::test]
     async fn map_frame() -> Result<(), RespError> {
         assert_frame!("%4\r\n", RespFrame::Map(4));
-        assert_frame_error!("%invalid\r\n", RespError::InvalidBlobLength);
+        assert_frame_error!("%invalid\r\n", RespError::InvalidLength);
         assert_frame_error!("%4", RespError::EndOfInput);
         Ok(())
     }
@@ -632,7 +1569,7 @@ mod tests {
     #[tokio::test]
     async fn attribute_frame() -> Result<(), RespError> {
         assert_frame!("|4\r\n", RespFrame::Attribute(4));
-        assert_frame_error!("|invalid\r\n", RespError::InvalidBlobLength);
+        assert_frame_error!("|invalid\r\n", RespError::InvalidLength);
         assert_frame_error!("|4", RespError::EndOfInput);
         Ok(())
     }
@@ -648,7 +1585,7 @@ mod tests {
     async fn push_frame() -> Result<(), RespError> {
         assert_frame!(">3\r\n", RespFrame::Push(3));
         assert_frame!(">32\r\n", RespFrame::Push(32));
-        assert_frame_error!(">invalid\r\n", RespError::InvalidBlobLength);
+        assert_frame_error!(">invalid\r\n", RespError::InvalidLength);
         assert_frame_error!(">3", RespError::EndOfInput);
         Ok(())
     }
@@ -657,7 +1594,7 @@ mod tests {
     async fn set_frame() -> Result<(), RespError> {
         assert_frame!("~2\r\n", RespFrame::Set(2));
         assert_frame!("~32\r\n", RespFrame::Set(32));
-        assert_frame_error!("~invalid\r\n", RespError::InvalidBlobLength);
+        assert_frame_error!("~invalid\r\n", RespError::InvalidLength);
         assert_frame_error!("~3", RespError::EndOfInput);
         Ok(())
     }
@@ -683,27 +1620,51 @@ mod tests {
         assert_frame_error!("=5", RespError::EndOfInput);
         let mut config = RespConfig::default();
         config.set_blob_limit(5);
-        assert_frame_error!(
-            "=10\r\ntxt:123456\r\n",
-            RespError::InvalidBlobLength,
-            config
-        );
+        assert_frame_error!("=10\r\ntxt:123456\r\n", RespError::TooBigBlob, config);
         Ok(())
     }
 
-    #[tokio::test]
-    async fn blob_error_frame() -> Result<(), RespError> {
-        assert_frame!("!4\r\ntest\r\n", RespFrame::BlobError("test".into()));
-        assert_frame_error!("!invalid\r\ntx\r\n", RespError::InvalidBlobLength);
-        assert_frame_error!("!4\r\n", RespError::EndOfInput);
-        assert_frame_error!("!4", RespError::EndOfInput);
-        let mut config = RespConfig::default();
-        config.set_blob_limit(5);
-        assert_frame_error!(
-            "!10\r\n1234567890\r\n",
-            RespError::InvalidBlobLength,
-            config
-        );
+    #[tokio::test]
+    async fn blob_error_frame() -> Result<(), RespError> {
+        assert_frame!("!4\r\ntest\r\n", RespFrame::BlobError("test".into()));
+        assert_frame_error!("!invalid\r\ntx\r\n", RespError::InvalidBlobLength);
+        assert_frame_error!("!4\r\n", RespError::EndOfInput);
+        assert_frame_error!("!4", RespError::EndOfInput);
+        let mut config = RespConfig::default();
+        config.set_blob_limit(5);
+        assert_frame_error!("!10\r\n1234567890\r\n", RespError::TooBigBlob, config);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn strict_version() -> Result<(), RespError> {
+        let mut config = RespConfig::default();
+        config.set_strict_version(true);
+
+        for input in ["_\r\n", "#t\r\n", "%1\r\n", ",1.5\r\n"] {
+            let mut reader = RespReader::new(input.as_bytes(), config.clone());
+            reader.set_version(RespVersion::V2);
+            assert!(matches!(reader.frame().await, Err(RespError::Version)));
+
+            let mut reader = RespReader::new(input.as_bytes(), config.clone());
+            reader.set_version(RespVersion::V3);
+            assert!(reader.frame().await?.is_some());
+        }
+
+        for input in ["$-1\r\n", "*-1\r\n"] {
+            let mut reader = RespReader::new(input.as_bytes(), config.clone());
+            reader.set_version(RespVersion::V3);
+            assert!(matches!(reader.frame().await, Err(RespError::Version)));
+
+            let mut reader = RespReader::new(input.as_bytes(), config.clone());
+            reader.set_version(RespVersion::V2);
+            assert_eq!(reader.frame().await?, Some(RespFrame::Nil));
+        }
+
+        let mut reader = RespReader::new("#t\r\n".as_bytes(), RespConfig::default());
+        reader.set_version(RespVersion::V2);
+        assert_eq!(reader.frame().await?, Some(RespFrame::Boolean(true)));
+
         Ok(())
     }
 
@@ -720,9 +1681,57 @@ mod tests {
         let mut reader = RespReader::new("invalid\r\n".as_bytes(), RespConfig::default());
         assert!(matches!(
             reader.read_size().await,
-            Err(RespError::InvalidBlobLength)
+            Err(RespError::InvalidLength)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_size_overflow() -> Result<(), RespError> {
+        // Larger than a 32-bit usize, but fits in a u64.
+        let mut reader = RespReader::new("$5000000000\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(reader.frame().await, Err(RespError::TooBigBlob)));
+
+        let mut reader = RespReader::new("5000000000\r\n".as_bytes(), RespConfig::default());
+        assert_eq!(reader.read_size_u64(b'*').await?, 5_000_000_000);
+
+        // Larger than a u64.
+        let mut reader =
+            RespReader::new("18446744073709551616\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_size_u64(b'*').await,
+            Err(RespError::InvalidLength)
+        ));
+        let input = "$18446744073709551616\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert!(matches!(reader.frame().await, Err(RespError::TooBigBlob)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_size_digits_limit() -> Result<(), RespError> {
+        let input = format!("{}1\r\n", "0".repeat(SIZE_DIGITS_LIMIT - 1));
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.read_size().await?, 1);
+
+        // Fails without reading the whole run of digits.
+        let input = Trickle {
+            input: &"0".repeat(1024 * 1024).into_bytes(),
+            chunk: 1,
+            reads: 0,
+        };
+        let mut reader = RespReader::new(input, RespConfig::default());
+        assert!(matches!(
+            reader.read_size().await,
+            Err(RespError::InvalidLength)
         ));
+        assert_eq!(reader.position(), SIZE_DIGITS_LIMIT + 1);
 
+        let input = format!("${}\r\n", "0".repeat(1024));
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert!(matches!(reader.frame().await, Err(RespError::TooBigBlob)));
         Ok(())
     }
 
@@ -730,7 +1739,7 @@ mod tests {
     async fn read_some_end_of_input() -> Result<(), RespError> {
         let mut reader = RespReader::new("".as_bytes(), RespConfig::default());
         assert!(matches!(
-            reader.read_some().await,
+            reader.read_some(1).await,
             Err(RespError::EndOfInput)
         ));
         Ok(())
@@ -772,6 +1781,111 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn read_line_positions() -> Result<(), RespError> {
+        for len in [0, 1, 7, 8, 15, 16, 17, 31, 32, 33, 100, 1000] {
+            let line = "x".repeat(len);
+            let input = format!("{}\r\n+OK\r\n", line);
+            let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+            assert_eq!(reader.read_line().await?, line.as_bytes());
+            assert_eq!(reader.read_line().await?, "+OK".as_bytes());
+        }
+
+        Ok(())
+    }
+
+    /// An [`AsyncRead`] that delivers at most `chunk` bytes per read and counts them.
+    struct Trickle<'a> {
+        input: &'a [u8],
+        chunk: usize,
+        reads: usize,
+    }
+
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let len = cmp::min(cmp::min(self.chunk, self.input.len()), buf.remaining());
+            if len > 0 {
+                let (chunk, rest) = self.input.split_at(len);
+                buf.put_slice(chunk);
+                self.input = rest;
+                self.reads += 1;
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn read_line_trickle() -> Result<(), RespError> {
+        let input = Trickle {
+            input: b"abcdefghijklmnopqrstuvwxyz\r\n",
+            chunk: 1,
+            reads: 0,
+        };
+        let mut reader = RespReader::new(input, RespConfig::default());
+        assert_eq!(
+            reader.read_line().await?,
+            "abcdefghijklmnopqrstuvwxyz".as_bytes()
+        );
+        assert_eq!(reader.inner.reads, 28);
+        // Each byte is scanned once, rather than rescanning the line after every read.
+        assert!(reader.scanned <= 28, "{} bytes scanned", reader.scanned);
+        assert_eq!(reader.peek().await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_line_lenient_newlines() -> Result<(), RespError> {
+        let config = RespConfig::builder().lenient_newlines(true).build();
+        let mut reader = RespReader::new("foo bar\nbaz\r\n+OK\n".as_bytes(), config);
+        assert_eq!(reader.read_line().await?, "foo bar".as_bytes());
+        assert_eq!(reader.read_line().await?, "baz".as_bytes());
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::SimpleString("OK".into()))
+        );
+
+        let mut reader = RespReader::new("foo bar\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_line().await,
+            Err(RespError::EndOfInput)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_line_partial_delivery() -> Result<(), RespError> {
+        for chunk in [1, 2, 3, 4, 5] {
+            let input = Trickle {
+                input: b"abc\r\nabcd\r\n",
+                chunk,
+                reads: 0,
+            };
+            let config = RespConfig::builder().inline_limit(3).build();
+            let mut reader = RespReader::new(input, config);
+            assert_eq!(reader.read_line().await?, "abc".as_bytes());
+            assert!(matches!(
+                reader.read_line().await,
+                Err(RespError::TooBigInline)
+            ));
+        }
+
+        let input = Trickle {
+            input: b"abc\r\n",
+            chunk: 4,
+            reads: 0,
+        };
+        let mut reader = RespReader::new(input, RespConfig::default());
+        assert_eq!(reader.read_line().await?, "abc".as_bytes());
+        assert_eq!(reader.inner.reads, 2);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn read_line_malformed_crlf() -> Result<(), RespError> {
         let mut reader = RespReader::new("abcdefg\rxxxxx".as_bytes(), RespConfig::default());
@@ -794,6 +1908,53 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn read_exact_buffered() -> Result<(), RespError> {
+        let mut reader = RespReader::new("".as_bytes(), RespConfig::default());
+        reader.buffer.extend_from_slice(&b"abcdefg".repeat(1000));
+        let capacity = reader.buffer.capacity();
+        for _ in 0..1000 {
+            assert_eq!(reader.read_exact(7).await?, Bytes::from_static(b"abcdefg"));
+            assert!(reader.buffer.capacity() <= capacity);
+        }
+        assert!(reader.buffer.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_exact_chunked() -> Result<(), RespError> {
+        let blob: Vec<u8> = (0..3 * READ_RESERVE_LIMIT + 7).map(|i| i as u8).collect();
+        for chunk in [1000, 4096, READ_RESERVE_LIMIT - 1, READ_RESERVE_LIMIT + 1] {
+            let input = Trickle {
+                input: &blob,
+                chunk,
+                reads: 0,
+            };
+            let mut reader = RespReader::new(input, RespConfig::default());
+            assert_eq!(reader.read_exact(5).await?, &blob[..5]);
+            assert_eq!(reader.read_exact(blob.len() - 5).await?, &blob[5..]);
+            assert_eq!(reader.peek().await?, None);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_exact_reserve_limit() -> Result<(), RespError> {
+        let (mut client, server) = tokio::io::duplex(64);
+        let mut reader = RespReader::new(server, RespConfig::default());
+        client.write_all(b"abc").await?;
+        drop(client);
+        assert!(matches!(
+            reader.read_exact(usize::MAX / 2).await,
+            Err(RespError::EndOfInput)
+        ));
+        assert!(reader.buffer.capacity() <= 2 * READ_RESERVE_LIMIT);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn read_exact_end_of_input() -> Result<(), RespError> {
         let mut reader = RespReader::new("abcd".as_bytes(), RespConfig::default());
@@ -830,8 +1991,10 @@ mod tests {
 
     #[tokio::test]
     async fn read_simple_string_value() -> Result<(), RespError> {
-        assert_value!("+foo\r\n", "foo");
-        assert_value!("*2\r\n+foo\r\n#t\r\n", ["foo", true]);
+        assert_value!("+foo\r\n", (+ "foo"));
+        assert_value!("*2\r\n+foo\r\n#t\r\n", [(+ "foo"), true]);
+        assert_value!("*2\r\n+OK\r\n$2\r\nOK\r\n", [(+ "OK"), "OK"]);
+        assert_ne!(resp! { (+ "OK") }, resp! { "OK" });
         Ok(())
     }
 
@@ -848,61 +2011,434 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn invalid_map() -> Result<(), RespError> {
-        assert_value_error!(
-            "%2\r\n$3\r\nfoo\r\n:1\r\n$3\r\nfoo\r\n:2\r\n",
-            RespError::InvalidMap
+    async fn total_elements_limit() -> Result<(), RespError> {
+        let input = "*2\r\n*2\r\n:1\r\n:2\r\n*2\r\n:3\r\n:4\r\n";
+
+        let mut config = RespConfig::default();
+        config.set_total_elements_limit(6);
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert_eq!(reader.value().await?, Some(resp! { [[1, 2], [3, 4]] }));
+
+        let mut config = RespConfig::default();
+        config.set_total_elements_limit(5);
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert!(matches!(
+            reader.value().await,
+            Err(RespError::TooManyElements)
+        ));
+
+        let mut config = RespConfig::default();
+        config.set_total_elements_limit(2);
+        let input = "*2\r\n:1\r\n:2\r\n*2\r\n:3\r\n:4\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert_eq!(reader.value().await?, Some(resp! { [1, 2] }));
+        assert_eq!(reader.value().await?, Some(resp! { [3, 4] }));
+
+        // Each map entry counts as two elements.
+        let input = "%2\r\n:1\r\n:2\r\n:3\r\n:4\r\n";
+        let config = RespConfig::builder().total_elements_limit(4).build();
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert_eq!(reader.value().await?, Some(resp! { {1 => 2, 3 => 4} }));
+        let config = RespConfig::builder().total_elements_limit(3).build();
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert!(matches!(
+            reader.value().await,
+            Err(RespError::TooManyElements)
+        ));
+
+        // Helpers that read several values apply the limit to the whole reply.
+        let inner = format!("*1000\r\n{}", ":1\r\n".repeat(1000));
+        let input = format!("*3\r\n{}", inner.repeat(3));
+        let config = RespConfig::builder().total_elements_limit(2500).build();
+        let mut reader = RespReader::new(input.as_bytes(), config.clone());
+        assert!(matches!(
+            reader.array_of(3).await,
+            Err(RespError::TooManyElements)
+        ));
+        let mut reader = RespReader::new(input.as_bytes(), config);
+        assert!(matches!(
+            reader.read_array_of(Ok).await,
+            Err(RespError::TooManyElements)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn double_keys() -> Result<(), RespError> {
+        assert_value_error!("~1\r\n,nan\r\n", RespError::RespPrimitive);
+        assert_value_error!("%1\r\n,nan\r\n:1\r\n", RespError::RespPrimitive);
+        assert_value!("%1\r\n:1\r\n,nan\r\n", {1i64 => (f64::NAN)});
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_map() -> Result<(), RespError> {
+        assert_value_error!(
+            "%2\r\n$3\r\nfoo\r\n:1\r\n$3\r\nfoo\r\n:2\r\n",
+            RespError::InvalidMap
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_set() -> Result<(), RespError> {
+        assert_value_error!("~2\r\n$3\r\nfoo\r\n$3\r\nfoo\r\n", RespError::InvalidSet);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_nil_value() -> Result<(), RespError> {
+        assert_value!("*2\r\n_\r\n_\r\n", [nil, nil]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_push_value() -> Result<(), RespError> {
+        assert_value!(">2\r\n+one\r\n+two\r\n", [> (+ "one"), (+ "two")]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_double_value() -> Result<(), RespError> {
+        assert_value!(",2.5\r\n", 2.5f64);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_verbatim_value() -> Result<(), RespError> {
+        assert_value!("=7\r\ntxt:abc\r\n", (= "txt", "abc"));
+        assert_value!("*2\r\n=7\r\ntxt:abc\r\n:1\r\n", [(= "txt", "abc"), 1i64]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_string_value() -> Result<(), RespError> {
+        assert_value!("$-1\r\n", nil);
+        assert_value!("$3\r\nabc\r\n", "abc");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_error() -> Result<(), RespError> {
+        assert_value!("-ERR foo\r\n", (!"ERR foo"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_attribute_value() -> Result<(), RespError> {
+        assert_value!(
+            "*1\r\n|2\r\n$3\r\nfoo\r\n:1\r\n$3\r\nbar\r\n:2\r\n",
+            [{a "foo" => 1, "bar" => 2}]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn last_attributes() -> Result<(), RespError> {
+        let input = "|1\r\n+ttl\r\n:3\r\n:1\r\n:2\r\n|1\r\n+a\r\n:1\r\n|1\r\n+b\r\n:2\r\n:3\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.last_attributes(), None);
+
+        assert_eq!(reader.value().await?, Some(resp! { 1 }));
+        assert_eq!(
+            reader.last_attributes().cloned().map(RespValue::Attribute),
+            Some(resp! { {a "ttl" => 3} })
+        );
+
+        assert_eq!(reader.value().await?, Some(resp! { 2 }));
+        assert_eq!(reader.last_attributes(), None);
+
+        // Only the last of several attributes is kept.
+        assert_eq!(reader.value().await?, Some(resp! { 3 }));
+        assert_eq!(
+            reader.last_attributes().cloned().map(RespValue::Attribute),
+            Some(resp! { {a "b" => 2} })
+        );
+
+        assert_eq!(reader.value().await?, None);
+        assert_eq!(reader.last_attributes(), None);
+
+        let mut reader = RespReader::new("|1\r\n+ttl\r\n:3\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(reader.value().await, Err(RespError::EndOfInput)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn value_with_attributes() -> Result<(), RespError> {
+        let input = "|1\r\n+ttl\r\n:3\r\n+foo\r\n+bar\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+
+        let (attributes, value) = reader.value_with_attributes().await?.unwrap();
+        assert_eq!(
+            attributes.map(RespValue::Attribute),
+            Some(resp! { {a "ttl" => 3} })
+        );
+        assert_eq!(value, resp! { (+ "foo") });
+
+        let (attributes, value) = reader.value_with_attributes().await?.unwrap();
+        assert_eq!(attributes, None);
+        assert_eq!(value, resp! { (+ "bar") });
+
+        assert_eq!(reader.value_with_attributes().await?, None);
+
+        let mut reader = RespReader::new("|1\r\n+ttl\r\n:3\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.value_with_attributes().await,
+            Err(RespError::EndOfInput)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_scalar_bytes() -> Result<(), RespError> {
+        let input = "$3\r\nfoo\r\n+bar\r\n=7\r\ntxt:baz\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.read_scalar_bytes().await?, Some("foo".into()));
+        assert_eq!(reader.read_scalar_bytes().await?, Some("bar".into()));
+        assert_eq!(reader.read_scalar_bytes().await?, Some("baz".into()));
+        assert_eq!(reader.read_scalar_bytes().await?, None);
+
+        for input in ["*1\r\n+foo\r\n", "%1\r\n", ":1\r\n", "_\r\n"] {
+            let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+            assert!(matches!(
+                reader.read_scalar_bytes().await,
+                Err(RespError::ExpectedString)
+            ));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn array_of() -> Result<(), RespError> {
+        let input = "*2\r\n+foo\r\n:1\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.array_of(2).await?,
+            vec![resp! { (+ "foo") }, resp! { 1 }]
+        );
+
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.array_of(3).await,
+            Err(RespError::UnexpectedArity {
+                expected: 3,
+                got: 2
+            })
+        ));
+
+        let mut reader = RespReader::new("+foo\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.array_of(1).await,
+            Err(RespError::ExpectedArray)
+        ));
+
+        let mut reader = RespReader::new("".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.array_of(1).await,
+            Err(RespError::EndOfInput)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_subscribe_confirms() -> Result<(), RespError> {
+        let input = concat!(
+            ">3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n",
+            "*3\r\n$9\r\nsubscribe\r\n$1\r\nb\r\n:2\r\n",
+        );
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.read_subscribe_confirms(2).await?,
+            vec![(Bytes::from("a"), 1), (Bytes::from("b"), 2)]
+        );
+
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_subscribe_confirms(3).await,
+            Err(RespError::EndOfInput)
+        ));
+
+        for input in [
+            ">3\r\n$7\r\nmessage\r\n$1\r\na\r\n:1\r\n",
+            ">3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n$1\r\n1\r\n",
+            ">2\r\n$9\r\nsubscribe\r\n$1\r\na\r\n",
+            "+subscribe\r\n",
+        ] {
+            let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+            assert!(matches!(
+                reader.read_subscribe_confirms(1).await,
+                Err(RespError::InvalidReply)
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_frame() -> Result<(), RespError> {
+        let config = RespConfig::default();
+        let cases = [
+            (&b"+OK\r\n"[..], RespFrame::SimpleString("OK".into())),
+            (b"$3\r\nfoo\r\n", RespFrame::BlobString("foo".into())),
+            (b"*2\r\n", RespFrame::Array(2)),
+            (b":-1\r\n", RespFrame::Integer(-1)),
+            (
+                b"=7\r\ntxt:abc\r\n",
+                RespFrame::Verbatim("txt".into(), "abc".into()),
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(super::parse_frame(input, &config)?, (expected, input.len()));
+        }
+
+        let (frame, len) = super::parse_frame(b"*2\r\n:1\r\n:2\r\n", &config)?;
+        assert_eq!((frame, len), (RespFrame::Array(2), 4));
+
+        for input in [&b""[..], b"+OK", b"$3\r\nfo", b"*2\r"] {
+            assert!(matches!(
+                super::parse_frame(input, &config),
+                Err(RespError::EndOfInput)
+            ));
+        }
+        assert!(matches!(
+            super::parse_frame(b"?\r\n", &config),
+            Err(RespError::UnknownType(b'?'))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_frame() {
+        let config = RespConfig::default();
+        for input in [
+            &b"+OK\r\n"[..],
+            b"$3\r\nfoo\r\n",
+            b"*2\r\n",
+            b":-1\r\n",
+            b"=7\r\ntxt:abc\r\n",
+        ] {
+            assert_eq!(super::validate_frame(input, &config).unwrap(), input.len());
+        }
+
+        assert!(matches!(
+            super::validate_frame(b"", &config),
+            Err(RespError::EndOfInput)
+        ));
+        assert!(matches!(
+            super::validate_frame(b"$3\r\nfo", &config),
+            Err(RespError::EndOfInput)
+        ));
+        assert!(matches!(
+            super::validate_frame(b"+OK\r\n+OK\r\n", &config),
+            Err(RespError::TrailingData)
+        ));
+        assert!(matches!(
+            super::validate_frame(b"?\r\n", &config),
+            Err(RespError::UnknownType(b'?'))
+        ));
+        assert!(matches!(
+            super::validate_frame(b":x\r\n", &config),
+            Err(RespError::InvalidInteger)
+        ));
+
+        let config = RespConfig::builder().blob_limit(2).build();
+        assert!(matches!(
+            super::validate_frame(b"$3\r\nfoo\r\n", &config),
+            Err(RespError::TooBigBlob)
+        ));
+    }
+
+    #[tokio::test]
+    async fn read_scan() -> Result<(), RespError> {
+        let input = "*2\r\n$2\r\n17\r\n*2\r\n$1\r\na\r\n$1\r\nb\r\n*2\r\n$1\r\n0\r\n*0\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.read_scan().await?,
+            ("17".into(), vec![resp! { "a" }, resp! { "b" }])
         );
-        Ok(())
-    }
+        assert_eq!(reader.read_scan().await?, ("0".into(), vec![]));
 
-    #[tokio::test]
-    async fn invalid_set() -> Result<(), RespError> {
-        assert_value_error!("~2\r\n$3\r\nfoo\r\n$3\r\nfoo\r\n", RespError::InvalidSet);
-        Ok(())
-    }
+        let mut reader = RespReader::new("*2\r\n:0\r\n*0\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_scan().await,
+            Err(RespError::InvalidReply)
+        ));
 
-    #[tokio::test]
-    async fn read_nil_value() -> Result<(), RespError> {
-        assert_value!("*2\r\n_\r\n_\r\n", [nil, nil]);
-        Ok(())
-    }
+        let mut reader =
+            RespReader::new("*2\r\n$1\r\n0\r\n:0\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_scan().await,
+            Err(RespError::InvalidReply)
+        ));
 
-    #[tokio::test]
-    async fn read_push_value() -> Result<(), RespError> {
-        assert_value!(">2\r\n+one\r\n+two\r\n", [> "one", "two"]);
+        let mut reader = RespReader::new("*1\r\n$1\r\n0\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_scan().await,
+            Err(RespError::UnexpectedArity {
+                expected: 2,
+                got: 1
+            })
+        ));
         Ok(())
     }
 
     #[tokio::test]
-    async fn read_double_value() -> Result<(), RespError> {
-        assert_value!(",2.5\r\n", 2.5f64);
-        Ok(())
-    }
+    async fn read_array_of() -> Result<(), RespError> {
+        let parse = |value| match value {
+            RespValue::Array(point) => match &point[..] {
+                [RespValue::String(x), RespValue::String(y)] => Ok(Some((x.clone(), y.clone()))),
+                _ => Err(RespError::InvalidReply),
+            },
+            RespValue::Nil => Ok(None),
+            _ => Err(RespError::InvalidReply),
+        };
 
-    #[tokio::test]
-    async fn read_verbatim_value() -> Result<(), RespError> {
-        assert_value!("=7\r\ntxt:abc\r\n", (= "txt", "abc"));
-        assert_value!("*2\r\n=7\r\ntxt:abc\r\n:1\r\n", [(= "txt", "abc"), 1i64]);
-        Ok(())
-    }
+        let input = "*3\r\n*2\r\n$3\r\n1.5\r\n$3\r\n2.5\r\n*-1\r\n*2\r\n$1\r\n3\r\n$1\r\n4\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.read_array_of(parse).await?,
+            vec![
+                Some(("1.5".into(), "2.5".into())),
+                None,
+                Some(("3".into(), "4".into())),
+            ]
+        );
 
-    #[tokio::test]
-    async fn read_string_value() -> Result<(), RespError> {
-        assert_value!("$-1\r\n", nil);
-        assert_value!("$3\r\nabc\r\n", "abc");
-        Ok(())
-    }
+        let mut reader = RespReader::new("*1\r\n:1\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_array_of(parse).await,
+            Err(RespError::InvalidReply)
+        ));
 
-    #[tokio::test]
-    async fn read_error() -> Result<(), RespError> {
-        assert_value!("-ERR foo\r\n", (!"ERR foo"));
+        let mut reader = RespReader::new(":1\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_array_of(parse).await,
+            Err(RespError::ExpectedArray)
+        ));
         Ok(())
     }
 
     #[tokio::test]
-    async fn read_attribute_value() -> Result<(), RespError> {
-        assert_value!("|2\r\n$3\r\nfoo\r\n:1\r\n$3\r\nbar\r\n:2\r\n", {a "foo" => 1, "bar" => 2});
+    async fn read_exec() -> Result<(), RespError> {
+        let input = "*3\r\n+OK\r\n-ERR wrong type\r\n:2\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.read_exec().await?,
+            Some(vec![
+                Ok(resp! { (+ "OK") }),
+                Err("ERR wrong type".into()),
+                Ok(resp! { 2 })
+            ])
+        );
+
+        let mut reader = RespReader::new("*-1\r\n_\r\n".as_bytes(), RespConfig::default());
+        assert_eq!(reader.read_exec().await?, None);
+        assert_eq!(reader.read_exec().await?, None);
+
+        let mut reader = RespReader::new("+OK\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.read_exec().await,
+            Err(RespError::InvalidReply)
+        ));
         Ok(())
     }
 
@@ -988,6 +2524,54 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn read_sized_argument_request() -> Result<(), RespError> {
+        let mut config = RespConfig::default();
+        config.set_sized_arguments(true);
+        let mut messages = request_messages!(b"*2\r\n$3\r\nget\r\n$0\r\n\r\n", config);
+        assert!(matches!(
+            messages.pop_front(),
+            Some(RespRequest::SizedArgument { size: 3, data }) if data == "get"
+        ));
+        assert!(matches!(
+            messages.pop_front(),
+            Some(RespRequest::SizedArgument { size: 0, data }) if data.is_empty()
+        ));
+        assert_ready!(messages);
+        assert_none!(messages);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_multibulk_counts() -> Result<(), RespError> {
+        let config = RespConfig::builder().multibulk_counts(true).build();
+        let input = b"*2\r\n$3\r\nget\r\n$1\r\na\r\nping\r\n*0\r\n";
+        let mut messages = request_messages!(input, config.clone());
+        assert!(matches!(
+            messages.pop_front(),
+            Some(RespRequest::Multibulk(2))
+        ));
+        assert_argument!(messages, b"get");
+        assert_argument!(messages, b"a");
+        assert_ready!(messages);
+        assert_argument!(messages, b"ping");
+        assert_ready!(messages);
+        assert!(matches!(
+            messages.pop_front(),
+            Some(RespRequest::Multibulk(0))
+        ));
+        assert_ready!(messages);
+        assert_none!(messages);
+
+        let mut reader = RespReader::new(&input[..], config);
+        assert_eq!(
+            reader.next_command().await?,
+            Some(vec!["get".into(), "a".into()])
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn read_inline_request() -> Result<(), RespError> {
         let mut messages = request_messages!(b"foo bar\r\nbaz bam\r\n");
@@ -1003,6 +2587,32 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn read_inline_disabled() -> Result<(), RespError> {
+        let mut config = RespConfig::default();
+        config.set_inline_enabled(false);
+        let mut messages = request_messages!(b"PING\r\n", config);
+        assert_error!(messages, RespError::InvalidInline);
+        assert_none!(messages);
+
+        let mut config = RespConfig::default();
+        config.set_inline_enabled(false);
+        let mut messages = request_messages!(b"*1\r\n$4\r\nPING\r\nPING\r\n", config);
+        assert_argument!(messages, b"PING");
+        assert_ready!(messages);
+        assert_error!(messages, RespError::InvalidInline);
+        assert_none!(messages);
+
+        let mut config = RespConfig::default();
+        config.set_inline_enabled(true);
+        let mut messages = request_messages!(b"PING\r\n", config);
+        assert_argument!(messages, b"PING");
+        assert_ready!(messages);
+        assert_none!(messages);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn read_invalid_argument() -> Result<(), RespError> {
         let mut messages = request_messages!(b"foo 'bar\r\nbaz bam\r\nfoo\r\n");
@@ -1027,6 +2637,14 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn read_invalid_multibulk_length() -> Result<(), RespError> {
+        let mut messages = request_messages!(b"*invalid\r\n$1\r\nx\r\n");
+        assert_error!(messages, RespError::InvalidLength);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn read_invalid_end_of_input() -> Result<(), RespError> {
         let mut messages = request_messages!(b"*2\r\n$1\r\nx\r\n$1\r\ny");
@@ -1042,7 +2660,23 @@ mod tests {
         config.set_blob_limit(5);
         let mut messages = request_messages!(b"*2\r\n$1\r\nx\r\n$10\r\n1234567890\r\n", config);
         assert_argument!(messages, b"x");
-        assert_error!(messages, RespError::InvalidBlobLength);
+        assert_error!(messages, RespError::TooBigBlob);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_pipelined_inline() -> Result<(), RespError> {
+        let mut config = RespConfig::default();
+        config.set_inline_limit(8);
+        let input = b"ping x\r\n".repeat(100);
+        let mut messages = request_messages!(input, config);
+        for _ in 0..100 {
+            assert_argument!(messages, b"ping");
+            assert_argument!(messages, b"x");
+            assert_ready!(messages);
+        }
+        assert_none!(messages);
 
         Ok(())
     }
@@ -1056,4 +2690,379 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn read_high_water() -> Result<(), RespError> {
+        let input = "+a\r\n+b\r\n+c\r\n";
+        let config = RespConfig::builder().read_high_water(4).build();
+        let mut reader = RespReader::new(input.as_bytes(), config.clone());
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::SimpleString("a".into()))
+        );
+        assert!(reader.buffer.is_empty());
+        assert_eq!(reader.position(), 4);
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::SimpleString("b".into()))
+        );
+        assert!(reader.buffer.is_empty());
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::SimpleString("c".into()))
+        );
+        assert_eq!(reader.frame().await?, None);
+
+        // A frame larger than the high water mark is still read.
+        let input = "$10\r\n0123456789\r\n:1\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), config.clone());
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::BlobString("0123456789".into()))
+        );
+        assert!(reader.buffer.len() <= 4);
+        assert_eq!(reader.frame().await?, Some(RespFrame::Integer(1)));
+
+        // A line longer than the high water mark is read in chunks, not a byte at a time.
+        let line = format!("+{}\r\n", "a".repeat(20_000));
+        let input = Trickle {
+            input: line.as_bytes(),
+            chunk: usize::MAX,
+            reads: 0,
+        };
+        let mut reader = RespReader::new(input, config.clone());
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::SimpleString("a".repeat(20_000).into()))
+        );
+        assert!(reader.inner.reads <= 4, "{} reads", reader.inner.reads);
+
+        let input = b"*1\r\n$1\r\na\r\nPING\r\n";
+        let mut messages = request_messages!(input, config);
+        assert_argument!(messages, b"a");
+        assert_ready!(messages);
+        assert_argument!(messages, b"PING");
+        assert_ready!(messages);
+        assert_none!(messages);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn next_command() -> Result<(), RespError> {
+        let input = "*2\r\n$3\r\nGET\r\n$1\r\na\r\nset b \"c d\"\r\n*0\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.next_command().await?,
+            Some(vec!["GET".into(), "a".into()])
+        );
+        assert_eq!(
+            reader.next_command().await?,
+            Some(vec!["set".into(), "b".into(), "c d".into()])
+        );
+        assert_eq!(reader.next_command().await?, Some(vec![]));
+        assert_eq!(reader.next_command().await?, None);
+
+        let config = RespConfig::builder().sized_arguments(true).build();
+        let mut reader = RespReader::new("*1\r\n$4\r\nPING\r\n".as_bytes(), config);
+        assert_eq!(reader.next_command().await?, Some(vec!["PING".into()]));
+
+        let mut reader = RespReader::new("get \"a\r\nPING\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.next_command().await,
+            Err(RespError::InvalidArgument)
+        ));
+        assert_eq!(reader.next_command().await?, Some(vec!["PING".into()]));
+
+        let mut reader = RespReader::new("*1\r\n$4\r\nPI".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.next_command().await,
+            Err(RespError::EndOfInput)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn inline_poll_limit() {
+        let config = RespConfig::builder().inline_poll_limit(2).build();
+        let mut reader = RespReader::new(&b"a b c d e\r\n"[..], config);
+        let requests = std::cell::RefCell::new(Vec::new());
+        let mut future = pin!(reader.requests(|request| requests.borrow_mut().push(request)));
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut context = Context::from_waker(&waker);
+        let mut counts = Vec::new();
+        while future.as_mut().poll(&mut context).is_pending() {
+            counts.push(requests.borrow().len());
+        }
+        assert_eq!(counts, vec![2, 4]);
+        assert_eq!(requests.borrow().len(), 6);
+        assert!(matches!(requests.borrow()[5], RespRequest::End));
+    }
+
+    #[tokio::test]
+    async fn skip_request_attributes() -> Result<(), RespError> {
+        let mut config = RespConfig::default();
+        config.set_skip_request_attributes(true);
+        let input = b"|1\r\n+trace\r\n*1\r\n:1\r\n*1\r\n$4\r\nPING\r\n|0\r\nECHO x\r\n";
+        let mut messages = request_messages!(input, config.clone());
+        assert_argument!(messages, b"PING");
+        assert_ready!(messages);
+        assert_argument!(messages, b"ECHO");
+        assert_argument!(messages, b"x");
+        assert_ready!(messages);
+        assert_none!(messages);
+
+        let mut messages = request_messages!(b"|1\r\n+a\r\n:1\r\n", config);
+        assert_error!(messages, RespError::EndOfInput);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_custom_lines() -> Result<(), RespError> {
+        let input = "@one 1\r\n@two\r\n:3\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.read_line().await?, "@one 1");
+        assert_eq!(reader.read_line().await?, "@two");
+        assert_eq!(reader.frame().await?, Some(RespFrame::Integer(3)));
+
+        let config = RespConfig::builder().inline_limit(4).build();
+        let mut reader = RespReader::new("@custom\r\n".as_bytes(), config);
+        assert!(matches!(
+            reader.read_line().await,
+            Err(RespError::TooBigInline)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lenient_newlines() -> Result<(), RespError> {
+        let config = RespConfig::builder().lenient_newlines(true).build();
+        let mut reader = RespReader::new("foo bar\n".as_bytes(), config);
+        assert_eq!(
+            reader.next_command().await?,
+            Some(vec!["foo".into(), "bar".into()])
+        );
+        assert_eq!(reader.next_command().await?, None);
+
+        let mut reader = RespReader::new("foo bar\n".as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.next_command().await,
+            Err(RespError::EndOfInput)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fail_on_nested_error() -> Result<(), RespError> {
+        let input = "*3\r\n$1\r\na\r\n-ERR x\r\n$1\r\nb\r\n-ERR y\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.value().await?,
+            Some(resp! { ["a", (! "ERR x"), "b"] })
+        );
+
+        let config = RespConfig::builder().fail_on_nested_error(true).build();
+        let mut reader = RespReader::new(input.as_bytes(), config.clone());
+        assert!(matches!(
+            reader.value().await,
+            Err(RespError::Server(error)) if error == "ERR x"
+        ));
+
+        // Top-level errors are still values.
+        let mut reader = RespReader::new("-ERR y\r\n".as_bytes(), config);
+        assert_eq!(reader.value().await?, Some(resp! { (! "ERR y") }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inline_only() -> Result<(), RespError> {
+        let mut config = RespConfig::default();
+        config.set_inline_only(true);
+        config.set_inline_enabled(false);
+        config.set_skip_request_attributes(true);
+        let input = b"PING\r\n*1 foo\r\n|1 x\r\nset a 'b c'\r\n";
+        let mut messages = request_messages!(input, config);
+        assert_argument!(messages, b"PING");
+        assert_ready!(messages);
+        assert_argument!(messages, b"*1");
+        assert_argument!(messages, b"foo");
+        assert_ready!(messages);
+        assert_argument!(messages, b"|1");
+        assert_argument!(messages, b"x");
+        assert_ready!(messages);
+        assert_argument!(messages, b"set");
+        assert_argument!(messages, b"a");
+        assert_argument!(messages, b"b c");
+        assert_ready!(messages);
+        assert_none!(messages);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reset() -> Result<(), RespError> {
+        let input = b"*2\r\n$3\r\nGET\r\n$1\r\na\r\n:1\r\n";
+        let mut reader = RespReader::new(&input[..], RespConfig::default());
+        reader.set_version(RespVersion::V3);
+        assert_eq!(
+            reader.next_command().await?,
+            Some(vec!["GET".into(), "a".into()])
+        );
+        assert!(!reader.buffer.is_empty());
+        let capacity = reader.buffer.capacity();
+
+        reader.reset(&b"PING\r\n>1\r\n:2\r\n:3\r\n"[..]);
+        assert_eq!(reader.version(), RespVersion::V2);
+        assert_eq!(reader.position(), 0);
+        assert!(reader.buffer.is_empty());
+        assert_eq!(reader.buffer.capacity(), capacity);
+        assert_eq!(reader.next_command().await?, Some(vec!["PING".into()]));
+        assert_eq!(reader.next_reply().await?, Some(resp! { 3 }));
+        assert_eq!(reader.take_pushes().len(), 1);
+        assert_eq!(reader.position(), 18);
+
+        reader.reset(&b""[..]);
+        assert_eq!(reader.next_command().await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn frame_ref() -> Result<(), RespError> {
+        let input = "*3\r\n$5\r\nhello\r\n=7\r\ntxt:abc\r\n+OK\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.frame_ref().await?, Some(RespFrameRef::Array(3)));
+        assert_eq!(
+            reader.frame_ref().await?,
+            Some(RespFrameRef::BlobString(b"hello"))
+        );
+        assert_eq!(
+            reader.frame_ref().await?,
+            Some(RespFrameRef::Verbatim(b"txt", b"abc"))
+        );
+        assert_eq!(
+            reader.frame_ref().await?,
+            Some(RespFrameRef::SimpleString(b"OK"))
+        );
+        assert_eq!(reader.frame_ref().await?, None);
+        assert_eq!(reader.borrowed, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn frame_ref_borrows_buffer() -> Result<(), RespError> {
+        let input = "$5\r\nhello\r\n+OK\r\n:1\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+
+        let Some(RespFrameRef::BlobString(value)) = reader.frame_ref().await? else {
+            panic!("expected a blob string");
+        };
+        let value = value.as_ptr_range();
+        assert_eq!(value, reader.buffer[4..9].as_ptr_range());
+        assert_eq!(reader.position(), 11);
+
+        let Some(RespFrameRef::SimpleString(value)) = reader.frame_ref().await? else {
+            panic!("expected a simple string");
+        };
+        let value = value.as_ptr_range();
+        assert_eq!(value, reader.buffer[1..3].as_ptr_range());
+        assert_eq!(reader.position(), 16);
+
+        // Any other read removes the borrowed frame first.
+        assert_eq!(reader.frame().await?, Some(RespFrame::Integer(1)));
+        assert_eq!(reader.position(), 20);
+        assert!(reader.buffer.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_counters() -> Result<(), RespError> {
+        let input = "*2\r\n:1\r\n%1\r\n+a\r\n$1\r\nb\r\n_\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!((reader.bytes_read(), reader.frames_read()), (0, 0));
+        reader.value().await?;
+        assert_eq!(reader.frames_read(), 5);
+        reader.frame().await?;
+        assert_eq!(reader.frames_read(), 6);
+        assert_eq!(reader.value().await?, None);
+        assert_eq!(reader.frames_read(), 6);
+        assert_eq!(reader.bytes_read(), input.len() as u64);
+
+        let input = b"*2\r\n$3\r\nGET\r\n$1\r\na\r\nPING\r\n";
+        let mut reader = RespReader::new(&input[..], RespConfig::default());
+        reader.next_command().await?;
+        assert_eq!(reader.frames_read(), 3);
+        reader.next_command().await?;
+        assert_eq!(reader.frames_read(), 4);
+        assert_eq!(reader.bytes_read(), input.len() as u64);
+
+        reader.reset(&b""[..]);
+        assert_eq!((reader.bytes_read(), reader.frames_read()), (0, 0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resync() -> Result<(), RespError> {
+        let input = "+OK\r\n?garbage\r\n$3\r\nfoo\r\n&x\ry\r\n:1\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::SimpleString("OK".into()))
+        );
+        assert!(matches!(
+            reader.frame().await,
+            Err(RespError::UnknownType(b'?'))
+        ));
+        assert_eq!(reader.resync().await?, 10);
+        assert_eq!(
+            reader.frame().await?,
+            Some(RespFrame::BlobString("foo".into()))
+        );
+        assert!(matches!(
+            reader.frame().await,
+            Err(RespError::UnknownType(b'&'))
+        ));
+        assert_eq!(reader.resync().await?, 6);
+        assert_eq!(reader.frame().await?, Some(RespFrame::Integer(1)));
+        assert_eq!(reader.resync().await?, 0);
+        assert_eq!(reader.frame().await?, None);
+
+        // Recovers across reads, even with the CRLF split between them.
+        let input = Trickle {
+            input: b"?garbage\r\n:2\r\n",
+            chunk: 1,
+            reads: 0,
+        };
+        let mut reader = RespReader::new(input, RespConfig::default());
+        assert!(reader.frame().await.is_err());
+        assert_eq!(reader.resync().await?, 10);
+        assert_eq!(reader.frame().await?, Some(RespFrame::Integer(2)));
+
+        // Skips everything if the stream ends first.
+        let mut reader = RespReader::new("?garbage\r".as_bytes(), RespConfig::default());
+        assert!(reader.frame().await.is_err());
+        assert_eq!(reader.resync().await?, 9);
+        assert_eq!(reader.frame().await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn attribute_before_key() -> Result<(), RespError> {
+        let input = "%2\r\n|1\r\n+ttl\r\n:3\r\n$1\r\na\r\n:1\r\n$1\r\nb\r\n:2\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.value().await?, Some(resp! { {"a" => 1, "b" => 2} }));
+
+        let input = "~2\r\n|1\r\n+a\r\n:1\r\n|0\r\n:1\r\n:2\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert_eq!(reader.value().await?, Some(resp! { {1, 2} }));
+
+        let input = "%1\r\n|0\r\n*0\r\n:1\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        assert!(matches!(
+            reader.value().await,
+            Err(RespError::RespPrimitive)
+        ));
+
+        let mut reader = RespReader::new("%1\r\n|0\r\n".as_bytes(), RespConfig::default());
+        assert!(matches!(reader.value().await, Err(RespError::EndOfInput)));
+        Ok(())
+    }
 }
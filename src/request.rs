@@ -7,6 +7,20 @@ pub enum RespRequest {
     /// One argument in a RESP request.
     Argument(Bytes),
 
+    /// One argument in a multibulk request, along with its declared size.
+    ///
+    /// Only produced when [`RespConfig::sized_arguments`] is enabled.
+    ///
+    /// [`RespConfig::sized_arguments`]: crate::RespConfig::sized_arguments
+    SizedArgument { size: usize, data: Bytes },
+
+    /// The declared number of arguments in a multibulk request, before its arguments.
+    ///
+    /// Only produced when [`RespConfig::multibulk_counts`] is enabled.
+    ///
+    /// [`RespConfig::multibulk_counts`]: crate::RespConfig::multibulk_counts
+    Multibulk(usize),
+
     /// An invalid argument in an inline request.
     InvalidArgument,
 
@@ -1,5 +1,4 @@
 use bytes::{BufMut, Bytes, BytesMut};
-use std::collections::VecDeque;
 
 /// Different modes of splitting arguments
 #[derive(Debug)]
@@ -17,11 +16,27 @@ enum State {
     DoubleQuotes,
 }
 
+/// The result of parsing one argument.
+#[derive(Debug)]
+enum Step<'a> {
+    /// An argument was parsed, followed by the remaining input.
+    Argument(&'a [u8]),
+
+    /// No arguments remain.
+    Done,
+
+    /// The input is invalid.
+    Invalid,
+}
+
 /// A single line argument iterator.
+///
+/// Arguments are parsed lazily, one at a time, as they are requested.
 #[derive(Debug, Default)]
 pub struct Splitter {
-    arguments: VecDeque<Bytes>,
     buffer: BytesMut,
+    line: Bytes,
+    offset: usize,
 }
 
 /// Split an inline request into arguments.
@@ -37,130 +52,144 @@ pub struct Splitter {
 ///   * Backspace: `\b`
 ///   * Alert/Bell: `\a`
 impl Splitter {
+    /// Parse the next argument from the current line.
     pub fn next(&mut self) -> Option<Bytes> {
-        self.arguments.pop_front()
+        let input = &self.line[self.offset..];
+        match parse(input, |b| self.buffer.put_u8(b)) {
+            Step::Argument(rest) => {
+                self.offset = self.line.len() - rest.len();
+                Some(self.buffer.split().freeze())
+            }
+            Step::Done | Step::Invalid => {
+                self.clear();
+                None
+            }
+        }
     }
 
-    pub fn split(&mut self, mut input: &[u8]) -> bool {
-        use State::*;
+    /// Validate `line` and prepare to parse its arguments with [`Splitter::next`].
+    pub fn split(&mut self, line: Bytes) -> bool {
+        self.clear();
 
-        let mut state = Trim;
-        self.buffer.reserve(input.len());
-
-        macro_rules! invalid {
-            () => {{
-                self.arguments.clear();
-                self.buffer.clear();
-                return false;
-            }};
+        let mut input = &line[..];
+        loop {
+            input = match parse(input, |_| {}) {
+                Step::Argument(rest) => rest,
+                Step::Done => break,
+                Step::Invalid => return false,
+            };
         }
 
-        macro_rules! push {
-            () => {{
-                self.arguments.push_back(self.buffer.split().freeze());
-            }};
-        }
+        self.line = line;
+        true
+    }
 
-        loop {
-            input = match state {
-                Trim => match input {
-                    [] => {
-                        return true;
-                    }
-                    [b'\'', rest @ ..] => {
-                        state = SingleQuotes;
-                        rest
-                    }
-                    [b'"', rest @ ..] => {
-                        state = DoubleQuotes;
-                        rest
-                    }
-                    [b, rest @ ..] if b.is_ascii_whitespace() => rest,
-                    _ => {
-                        state = NoQuotes;
-                        continue;
-                    }
-                },
-                NoQuotes => match input {
-                    [] => {
-                        push!();
-                        return true;
-                    }
-                    [b, rest @ ..] if b.is_ascii_whitespace() => {
-                        state = Trim;
-                        push!();
-                        rest
-                    }
-                    [b, rest @ ..] => {
-                        self.buffer.put_u8(*b);
-                        rest
-                    }
-                },
-                SingleQuotes => match input {
-                    [] => {
-                        invalid!();
-                    }
-                    [b'\'', b, ..] if !b.is_ascii_whitespace() => {
-                        invalid!();
-                    }
-                    [b'\'', rest @ ..] => {
-                        state = Trim;
-                        push!();
-                        rest
-                    }
-                    [b'\\', b'\'', rest @ ..] => {
-                        self.buffer.put_u8(b'\'');
-                        rest
-                    }
-                    [b, rest @ ..] => {
-                        self.buffer.put_u8(*b);
-                        rest
-                    }
-                },
-                DoubleQuotes => match input {
-                    [] => {
-                        invalid!();
-                    }
-                    [b'"', b, ..] if !b.is_ascii_whitespace() => {
-                        invalid!();
-                    }
-                    [b'"', rest @ ..] => {
-                        state = Trim;
-                        push!();
-                        rest
-                    }
-                    [b'\\', b'x', a, b, rest @ ..] => {
-                        let array = &[*a, *b][..];
-                        let string = std::str::from_utf8(array).ok();
-                        let byte = string.and_then(|string| u8::from_str_radix(string, 16).ok());
-
-                        if let Some(byte) = byte {
-                            self.buffer.put_u8(byte);
-                        } else {
-                            self.buffer.put_u8(b'x');
-                            self.buffer.put_u8(*a);
-                            self.buffer.put_u8(*b);
-                        }
-
-                        rest
-                    }
-                    [b'\\', b, rest @ ..] => {
-                        self.buffer.put_u8(match b {
-                            b'a' => b'\x07',
-                            b'b' => b'\x08',
-                            b'n' => b'\n',
-                            b'r' => b'\r',
-                            b't' => b'\t',
-                            _ => *b,
-                        });
-                        rest
-                    }
-                    [b, rest @ ..] => {
-                        self.buffer.put_u8(*b);
-                        rest
+    /// Discard the current line and any partial argument.
+    fn clear(&mut self) {
+        self.buffer.clear();
+        self.line = Bytes::new();
+        self.offset = 0;
+    }
+}
+
+/// Parse one argument from the front of `input`, passing each byte to `put`.
+fn parse(mut input: &[u8], mut put: impl FnMut(u8)) -> Step<'_> {
+    use State::*;
+
+    let mut state = Trim;
+
+    loop {
+        input = match state {
+            Trim => match input {
+                [] => {
+                    return Step::Done;
+                }
+                [b'\'', rest @ ..] => {
+                    state = SingleQuotes;
+                    rest
+                }
+                [b'"', rest @ ..] => {
+                    state = DoubleQuotes;
+                    rest
+                }
+                [b, rest @ ..] if b.is_ascii_whitespace() => rest,
+                _ => {
+                    state = NoQuotes;
+                    continue;
+                }
+            },
+            NoQuotes => match input {
+                [] => {
+                    return Step::Argument(input);
+                }
+                [b, rest @ ..] if b.is_ascii_whitespace() => {
+                    return Step::Argument(rest);
+                }
+                [b, rest @ ..] => {
+                    put(*b);
+                    rest
+                }
+            },
+            SingleQuotes => match input {
+                [] => {
+                    return Step::Invalid;
+                }
+                [b'\'', b, ..] if !b.is_ascii_whitespace() => {
+                    return Step::Invalid;
+                }
+                [b'\'', rest @ ..] => {
+                    return Step::Argument(rest);
+                }
+                [b'\\', b'\'', rest @ ..] => {
+                    put(b'\'');
+                    rest
+                }
+                [b, rest @ ..] => {
+                    put(*b);
+                    rest
+                }
+            },
+            DoubleQuotes => match input {
+                [] => {
+                    return Step::Invalid;
+                }
+                [b'"', b, ..] if !b.is_ascii_whitespace() => {
+                    return Step::Invalid;
+                }
+                [b'"', rest @ ..] => {
+                    return Step::Argument(rest);
+                }
+                [b'\\', b'x', a, b, rest @ ..] => {
+                    let array = &[*a, *b][..];
+                    let string = std::str::from_utf8(array).ok();
+                    let byte = string.and_then(|string| u8::from_str_radix(string, 16).ok());
+
+                    if let Some(byte) = byte {
+                        put(byte);
+                    } else {
+                        put(b'x');
+                        put(*a);
+                        put(*b);
                     }
-                },
-            }
+
+                    rest
+                }
+                [b'\\', b, rest @ ..] => {
+                    put(match b {
+                        b'a' => b'\x07',
+                        b'b' => b'\x08',
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        _ => *b,
+                    });
+                    rest
+                }
+                [b, rest @ ..] => {
+                    put(*b);
+                    rest
+                }
+            },
         }
     }
 }
@@ -172,12 +201,12 @@ mod tests {
     macro_rules! assert_split {
         ($input:expr) => {
             let mut splitter = Splitter::default();
-            assert!(splitter.split(&$input[..]));
+            assert!(splitter.split(Bytes::from_static(&$input[..])));
             assert_eq!(splitter.next(), None);
         };
         ($input:expr, $($expected:expr),*) => {
             let mut splitter = Splitter::default();
-            assert!(splitter.split(&$input[..]));
+            assert!(splitter.split(Bytes::from_static(&$input[..])));
             let mut actual = Vec::new();
             while let Some(argument) = splitter.next() {
                 actual.push(argument);
@@ -190,7 +219,7 @@ mod tests {
     macro_rules! assert_no_split {
         ($input:expr) => {
             let mut splitter = Splitter::default();
-            assert!(!splitter.split(&$input[..]));
+            assert!(!splitter.split(Bytes::from_static(&$input[..])));
             assert_eq!(None, splitter.next());
             assert!(splitter.buffer.is_empty());
         };
@@ -250,4 +279,19 @@ mod tests {
     fn backspace() {
         assert_split!(b" \"\\b\" ", b"\x08");
     }
+
+    #[test]
+    fn incremental() {
+        let mut splitter = Splitter::default();
+        assert!(splitter.split(Bytes::from_static(b"get 'x y' z")));
+        assert_eq!(splitter.offset, 0);
+        assert_eq!(splitter.next(), Some(Bytes::from_static(b"get")));
+        assert_eq!(splitter.offset, 4);
+        assert_eq!(splitter.next(), Some(Bytes::from_static(b"x y")));
+        assert_eq!(splitter.offset, 9);
+        assert_eq!(splitter.next(), Some(Bytes::from_static(b"z")));
+        assert_eq!(splitter.offset, 11);
+        assert_eq!(splitter.next(), None);
+        assert!(splitter.line.is_empty());
+    }
 }
@@ -1,4 +1,4 @@
-use crate::RespPrimitive;
+use crate::{RespError, RespFrame, RespPrimitive, RespVersion};
 use bytes::Bytes;
 use ordered_float::OrderedFloat;
 use std::collections::{BTreeMap, BTreeSet};
@@ -20,7 +20,11 @@ pub enum RespValue {
     Nil,
     Push(Vec<RespValue>),
     Set(BTreeSet<RespPrimitive>),
+    /// A simple string, such as a `+OK` status reply.
+    SimpleString(Bytes),
     String(Bytes),
+    /// A frame of an unknown type and its raw line, for passing through.
+    Unknown(u8, Bytes),
     Verbatim(Bytes, Bytes),
 }
 
@@ -72,6 +76,59 @@ impl From<Vec<u8>> for RespValue {
     }
 }
 
+impl From<RespPrimitive> for RespValue {
+    fn from(value: RespPrimitive) -> Self {
+        match value {
+            RespPrimitive::Integer(value) => RespValue::Integer(value),
+            RespPrimitive::Nil => RespValue::Nil,
+            RespPrimitive::String(value) => RespValue::String(value),
+        }
+    }
+}
+
+impl TryFrom<RespFrame> for RespValue {
+    type Error = RespError;
+
+    /// Convert a scalar frame into a value.
+    ///
+    /// Returns [`RespError::ExpectedScalar`] for aggregate frames, which need their children.
+    fn try_from(frame: RespFrame) -> Result<Self, RespError> {
+        use RespFrame::*;
+
+        Ok(match frame {
+            Array(_) | Attribute(_) | Map(_) | Push(_) | Set(_) => {
+                return Err(RespError::ExpectedScalar)
+            }
+            Bignum(value) => RespValue::Bignum(value),
+            BlobError(value) | SimpleError(value) => RespValue::Error(value),
+            BlobString(value) => RespValue::String(value),
+            Boolean(value) => RespValue::Boolean(value),
+            Double(value) => RespValue::Double(value),
+            Integer(value) => RespValue::Integer(value),
+            Nil => RespValue::Nil,
+            SimpleString(value) => RespValue::SimpleString(value),
+            Unknown(kind, value) => RespValue::Unknown(kind, value),
+            Verbatim(format, value) => RespValue::Verbatim(format, value),
+        })
+    }
+}
+
+impl<T: Into<RespValue>> FromIterator<T> for RespValue {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        RespValue::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<RespPrimitive>, V: Into<RespValue>> FromIterator<(K, V)> for RespValue {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        RespValue::Map(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
 impl RespValue {
     /// Extract a [`Vec`] of values, if this value is an array.
     pub fn array(&mut self) -> Option<&mut Vec<RespValue>> {
@@ -82,6 +139,171 @@ impl RespValue {
         }
     }
 
+    /// Compare two values, allowing doubles to differ by up to `epsilon`.
+    ///
+    /// All other values, including map keys, must be exactly equal.
+    pub fn approx_eq(&self, other: &RespValue, epsilon: f64) -> bool {
+        use RespValue::*;
+
+        match (self, other) {
+            (Double(a), Double(b)) => a == b || (a.0 - b.0).abs() <= epsilon,
+            (Array(a), Array(b)) | (Push(a), Push(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.approx_eq(b, epsilon))
+            }
+            (Attribute(a), Attribute(b)) | (Map(a), Map(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((ak, av), (bk, bv))| ak == bk && av.approx_eq(bv, epsilon))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Calculate the number of bytes this value occupies when written in `version`.
+    pub fn byte_size(&self, version: RespVersion) -> usize {
+        use RespValue::*;
+
+        let v3 = version == RespVersion::V3;
+        match self {
+            Attribute(map) => {
+                header_size(map.len())
+                    + map
+                        .iter()
+                        .map(|(key, value)| key.byte_size(version) + value.byte_size(version))
+                        .sum::<usize>()
+            }
+            Array(values) | Push(values) => {
+                header_size(values.len())
+                    + values
+                        .iter()
+                        .map(|value| value.byte_size(version))
+                        .sum::<usize>()
+            }
+            Error(value) if v3 && value.iter().any(|&b| b == b'\r' || b == b'\n') => {
+                blob_size(value.len())
+            }
+            Bignum(value) | Error(value) | SimpleString(value) | Unknown(_, value) => {
+                line_size(value.len())
+            }
+            Boolean(_) => 4,
+            Double(value) => line_size(value.to_string().len()),
+            Integer(value) => integer_size(*value),
+            Map(map) => {
+                header_size(if v3 { map.len() } else { 2 * map.len() })
+                    + map
+                        .iter()
+                        .map(|(key, value)| key.byte_size(version) + value.byte_size(version))
+                        .sum::<usize>()
+            }
+            Nil => nil_size(version),
+            Set(set) => {
+                header_size(set.len())
+                    + set
+                        .iter()
+                        .map(|value| value.byte_size(version))
+                        .sum::<usize>()
+            }
+            String(value) => blob_size(value.len()),
+            Verbatim(format, value) if v3 => blob_size(format.len() + 1 + value.len()),
+            Verbatim(_, value) => blob_size(value.len()),
+        }
+    }
+
+    /// Compare two values, skipping any attributes in arrays and pushes.
+    ///
+    /// Attributes are metadata, so replies that differ only in their attributes are equal.
+    /// Any two attributes are equal to each other.
+    pub fn eq_ignoring_attributes(&self, other: &RespValue) -> bool {
+        use RespValue::*;
+
+        fn skip_attributes(values: &[RespValue]) -> impl Iterator<Item = &RespValue> {
+            values
+                .iter()
+                .filter(|value| !matches!(value, RespValue::Attribute(_)))
+        }
+
+        match (self, other) {
+            (Attribute(_), Attribute(_)) => true,
+            (Array(a), Array(b)) | (Push(a), Push(b)) => {
+                let mut a = skip_attributes(a);
+                let mut b = skip_attributes(b);
+                loop {
+                    match (a.next(), b.next()) {
+                        (Some(a), Some(b)) if a.eq_ignoring_attributes(b) => {}
+                        (None, None) => return true,
+                        _ => return false,
+                    }
+                }
+            }
+            (Map(a), Map(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((ak, av), (bk, bv))| ak == bk && av.eq_ignoring_attributes(bv))
+            }
+            _ => self == other,
+        }
+    }
+
+    /// The maximum nesting depth of this value.
+    ///
+    /// Scalars have a depth of one, and each aggregate adds one to the depth of its deepest
+    /// child, matching [`RespWriter::set_depth_limit`].
+    ///
+    /// [`RespWriter::set_depth_limit`]: crate::RespWriter::set_depth_limit
+    pub fn depth(&self) -> usize {
+        use RespValue::*;
+
+        1 + match self {
+            Array(values) | Push(values) => values.iter().map(RespValue::depth).max().unwrap_or(0),
+            Attribute(map) | Map(map) => map.values().map(RespValue::depth).max().unwrap_or(0),
+            Set(set) if !set.is_empty() => 1,
+            _ => 0,
+        }
+    }
+
+    /// Count the frames in this value, including itself and all of its children.
+    pub fn frame_count(&self) -> usize {
+        use RespValue::*;
+
+        1 + match self {
+            Array(values) | Push(values) => values.iter().map(RespValue::frame_count).sum(),
+            Attribute(map) | Map(map) => map.values().map(|value| 1 + value.frame_count()).sum(),
+            Set(set) => set.len(),
+            _ => 0,
+        }
+    }
+
+    /// Get the element at `index`, if this value is an array or push.
+    pub fn get(&self, index: usize) -> Option<&RespValue> {
+        match self {
+            RespValue::Array(values) | RespValue::Push(values) => values.get(index),
+            _ => None,
+        }
+    }
+
+    /// The kind of a push, such as `message` or `subscribe`, taken from its first element.
+    ///
+    /// Returns `None` unless this value is a push starting with a string.
+    pub fn push_kind(&self) -> Option<&[u8]> {
+        match self {
+            RespValue::Push(values) => match values.first()? {
+                RespValue::SimpleString(kind) | RespValue::String(kind) => Some(kind),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Get the value for `key`, if this value is a map or attribute.
+    pub fn get_key(&self, key: &RespPrimitive) -> Option<&RespValue> {
+        match self {
+            RespValue::Attribute(map) | RespValue::Map(map) => map.get(key),
+            _ => None,
+        }
+    }
+
     /// Extract an error message if this value is an error.
     pub fn error(&self) -> Option<&str> {
         if let RespValue::Error(value) = self {
@@ -100,11 +322,216 @@ impl RespValue {
         }
     }
 
+    /// Coerce this value into a [`bool`], for replies that encode booleans in other ways.
+    ///
+    /// * Booleans are returned as is.
+    /// * Integers `0` and `1` are `false` and `true`.
+    /// * Strings `OK` and `true` are `true`, and `false` is `false`.
+    pub fn as_bool_lossy(&self) -> Option<bool> {
+        match self {
+            RespValue::Boolean(value) => Some(*value),
+            RespValue::Integer(0) => Some(false),
+            RespValue::Integer(1) => Some(true),
+            RespValue::SimpleString(value) | RespValue::String(value) => match &value[..] {
+                b"OK" | b"true" => Some(true),
+                b"false" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Reconstruct a verbatim value from a string that was downgraded for V2.
+    ///
+    /// A string is treated as verbatim if it starts with a three byte format followed by `:`.
+    pub fn as_verbatim_lossy(&self) -> Option<RespValue> {
+        match self {
+            RespValue::String(value) if value.get(3) == Some(&b':') => {
+                Some(RespValue::Verbatim(value.slice(..3), value.slice(4..)))
+            }
+            RespValue::Verbatim(..) => Some(self.clone()),
+            _ => None,
+        }
+    }
+
+    /// Format this value the way `redis-cli` displays replies, indented by `indent` spaces.
+    ///
+    /// Every line ends with a newline, and the first line isn't indented, since the caller is
+    /// expected to have written a prefix already.
+    ///
+    /// ```
+    /// # use respite::resp;
+    /// let value = resp! { [1, ["a", nil]] };
+    /// assert_eq!(
+    ///     value.redis_cli_format(0),
+    ///     "1) (integer) 1\n2) 1) \"a\"\n   2) (nil)\n",
+    /// );
+    /// ```
+    pub fn redis_cli_format(&self, indent: usize) -> String {
+        let mut output = String::new();
+        self.redis_cli_format_into(&mut output, &" ".repeat(indent));
+        output
+    }
+
+    /// Format this value like `redis-cli` into `output`, with `prefix` before each nested line.
+    fn redis_cli_format_into(&self, output: &mut String, prefix: &str) {
+        use RespValue::*;
+
+        type Entries<'a> =
+            Box<dyn Iterator<Item = (RedisCliEntry<'a>, Option<&'a RespValue>)> + 'a>;
+        let (entries, len, kind, separator): (Entries<'_>, _, _, _) = match self {
+            Array(values) | Push(values) => (
+                Box::new(
+                    values
+                        .iter()
+                        .map(|value| (RedisCliEntry::Value(value), None)),
+                ),
+                values.len(),
+                if let Array(_) = self { "array" } else { "push" },
+                ')',
+            ),
+            Set(set) => (
+                Box::new(
+                    set.iter()
+                        .map(|value| (RedisCliEntry::Primitive(value), None)),
+                ),
+                set.len(),
+                "set",
+                '~',
+            ),
+            Attribute(map) | Map(map) => (
+                Box::new(
+                    map.iter()
+                        .map(|(key, value)| (RedisCliEntry::Primitive(key), Some(value))),
+                ),
+                map.len(),
+                "hash",
+                '#',
+            ),
+            Bignum(value) => return redis_cli_line(output, "(big number) ", value),
+            Boolean(value) => return output.push_str(&format!("({})\n", value)),
+            Double(value) => return output.push_str(&format!("(double) {}\n", value)),
+            Error(value) => return redis_cli_line(output, "(error) ", value),
+            Integer(value) => return output.push_str(&format!("(integer) {}\n", value)),
+            Nil => return output.push_str("(nil)\n"),
+            SimpleString(value) | Verbatim(_, value) => return redis_cli_line(output, "", value),
+            String(value) => {
+                output.push_str(&redis_cli_quote(value));
+                return output.push('\n');
+            }
+            Unknown(kind, value) => {
+                output.push_str(&format!("(unknown {:?}) ", char::from(*kind)));
+                output.push_str(&redis_cli_quote(value));
+                return output.push('\n');
+            }
+        };
+
+        if len == 0 {
+            return output.push_str(&format!("(empty {})\n", kind));
+        }
+
+        let width = digits(len as u64);
+        let nested = format!("{}{}", prefix, " ".repeat(width + 2));
+
+        for (index, (entry, value)) in entries.enumerate() {
+            let prefix = if index == 0 { "" } else { prefix };
+            output.push_str(&format!("{}{:>width$}{} ", prefix, index + 1, separator));
+            entry.format_into(output, &nested);
+            if let Some(value) = value {
+                output.pop();
+                output.push_str(" => ");
+                value.redis_cli_format_into(output, &nested);
+            }
+        }
+    }
+
+    /// Convert maps and attributes into arrays of alternating keys and values, like
+    /// [`RespWriter::write_map`] does in V2.
+    ///
+    /// Nested maps are flattened too, including those in arrays and pushes.
+    ///
+    /// [`RespWriter::write_map`]: crate::RespWriter::write_map
+    pub fn flatten_map(self) -> RespValue {
+        use RespValue::*;
+
+        match self {
+            Attribute(map) | Map(map) => Array(
+                map.into_iter()
+                    .flat_map(|(key, value)| [key.into(), value.flatten_map()])
+                    .collect(),
+            ),
+            Array(values) => Array(values.into_iter().map(RespValue::flatten_map).collect()),
+            Push(values) => Push(values.into_iter().map(RespValue::flatten_map).collect()),
+            value => value,
+        }
+    }
+
+    /// Build a map from key-value pairs.
+    ///
+    /// Returns [`RespError::RespPrimitive`] if any key isn't a primitive, or
+    /// [`RespError::InvalidMap`] if any key is repeated.
+    pub fn map_from_pairs<K, V, I>(pairs: I) -> Result<RespValue, RespError>
+    where
+        K: Into<RespValue>,
+        V: Into<RespValue>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        // Bytes is a false positive here.
+        // <https://rust-lang.github.io/rust-clippy/master/index.html#mutable_key_type>
+        #[allow(clippy::mutable_key_type)]
+        let mut map = BTreeMap::new();
+        for (key, value) in pairs {
+            let key: RespPrimitive = key.into().try_into()?;
+            if map.insert(key, value.into()).is_some() {
+                return Err(RespError::InvalidMap);
+            }
+        }
+        Ok(RespValue::Map(map))
+    }
+
+    /// Build a set from values.
+    ///
+    /// Returns [`RespError::RespPrimitive`] if any value isn't a primitive, or
+    /// [`RespError::InvalidSet`] if any value is repeated.
+    pub fn set_from<T, I>(values: I) -> Result<RespValue, RespError>
+    where
+        T: Into<RespValue>,
+        I: IntoIterator<Item = T>,
+    {
+        // Bytes is a false positive here.
+        // <https://rust-lang.github.io/rust-clippy/master/index.html#mutable_key_type>
+        #[allow(clippy::mutable_key_type)]
+        let mut set = BTreeSet::new();
+        for value in values {
+            if !set.insert(value.into().try_into()?) {
+                return Err(RespError::InvalidSet);
+            }
+        }
+        Ok(RespValue::Set(set))
+    }
+
+    /// Convert a map into a map of string keys to values.
+    ///
+    /// Returns [`RespError::ExpectedMap`] if this isn't a map, or [`RespError::ExpectedString`] if
+    /// any key isn't a string.
+    pub fn into_string_map(self) -> Result<BTreeMap<Bytes, RespValue>, RespError> {
+        let RespValue::Map(map) = self else {
+            return Err(RespError::ExpectedMap);
+        };
+
+        map.into_iter()
+            .map(|(key, value)| match key {
+                RespPrimitive::String(key) => Ok((key, value)),
+                _ => Err(RespError::ExpectedString),
+            })
+            .collect()
+    }
+
     /// Extract the text value of this value if it has one.
     pub fn text(&self) -> Option<&str> {
         use RespValue::*;
 
-        if let String(text) | Verbatim(_, text) = self {
+        if let SimpleString(text) | String(text) | Verbatim(_, text) = self {
             std::str::from_utf8(text).ok()
         } else {
             None
@@ -112,9 +539,97 @@ impl RespValue {
     }
 }
 
+/// An element of an aggregate being formatted like `redis-cli`, borrowed from the value.
+enum RedisCliEntry<'a> {
+    Primitive(&'a RespPrimitive),
+    Value(&'a RespValue),
+}
+
+impl RedisCliEntry<'_> {
+    /// Format this entry into `output`, with `prefix` before each nested line.
+    fn format_into(&self, output: &mut String, prefix: &str) {
+        match self {
+            RedisCliEntry::Primitive(RespPrimitive::Integer(value)) => {
+                output.push_str(&format!("(integer) {}\n", value))
+            }
+            RedisCliEntry::Primitive(RespPrimitive::Nil) => output.push_str("(nil)\n"),
+            RedisCliEntry::Primitive(RespPrimitive::String(value)) => {
+                output.push_str(&redis_cli_quote(value));
+                output.push('\n');
+            }
+            RedisCliEntry::Value(value) => value.redis_cli_format_into(output, prefix),
+        }
+    }
+}
+
+/// Write a line of raw bytes after `label`, like `redis-cli` does for statuses and errors.
+fn redis_cli_line(output: &mut String, label: &str, value: &[u8]) {
+    output.push_str(label);
+    output.push_str(&String::from_utf8_lossy(value));
+    output.push('\n');
+}
+
+/// Quote and escape bytes, like `redis-cli` does for strings.
+fn redis_cli_quote(value: &[u8]) -> String {
+    let mut output = String::from("\"");
+    for &b in value {
+        match b {
+            b'"' | b'\\' => {
+                output.push('\\');
+                output.push(char::from(b));
+            }
+            b'\n' => output.push_str("\\n"),
+            b'\r' => output.push_str("\\r"),
+            b'\t' => output.push_str("\\t"),
+            0x07 => output.push_str("\\a"),
+            0x08 => output.push_str("\\b"),
+            b' '..=b'~' => output.push(char::from(b)),
+            _ => output.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// The number of digits in `value`.
+fn digits(value: u64) -> usize {
+    value
+        .checked_ilog10()
+        .map_or(1, |digits| digits as usize + 1)
+}
+
+/// The size of a frame with a single line of `len` bytes, including the type byte.
+fn line_size(len: usize) -> usize {
+    1 + len + 2
+}
+
+/// The size of an aggregate or blob header for `len`.
+fn header_size(len: usize) -> usize {
+    line_size(digits(len as u64))
+}
+
+/// The size of a blob frame containing `len` bytes.
+pub(crate) fn blob_size(len: usize) -> usize {
+    header_size(len) + len + 2
+}
+
+/// The size of an integer frame.
+pub(crate) fn integer_size(value: i64) -> usize {
+    line_size(usize::from(value < 0) + digits(value.unsigned_abs()))
+}
+
+/// The size of a nil frame.
+pub(crate) fn nil_size(version: RespVersion) -> usize {
+    match version {
+        RespVersion::V2 => 5,
+        RespVersion::V3 => 3,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::RespWriter;
 
     #[test]
     fn array() {
@@ -150,6 +665,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn blob() {
+        let data = Bytes::from_static(b"\x00\xff");
+        assert_eq!(
+            RespValue::String(data.clone()),
+            resp! { (blob data.clone()) }
+        );
+        assert_eq!(
+            RespValue::String(data.clone()),
+            resp! { (blob vec![0u8, 255]) }
+        );
+        assert_eq!(
+            RespValue::Array(vec![RespValue::String(data.clone()), RespValue::Nil]),
+            resp! { [(blob data.clone()), nil] }
+        );
+        assert_eq!(
+            resp! { {"key" => (blob data.clone())} }.get_key(&"key".into()),
+            Some(&RespValue::String(data))
+        );
+    }
+
+    #[test]
+    fn simple_string() {
+        assert_eq!(RespValue::SimpleString("OK".into()), resp! { (+ "OK") });
+        assert_eq!(resp! { (+ "OK") }.text(), Some("OK"));
+        let status = String::from("QUEUED");
+        assert_eq!(
+            RespValue::SimpleString("QUEUED".into()),
+            resp! { (+ status.clone()) }
+        );
+        assert_eq!(
+            RespValue::Array(vec![
+                RespValue::SimpleString("OK".into()),
+                RespValue::String("OK".into()),
+                RespValue::SimpleString("QUEUED".into()),
+            ]),
+            resp! { [(+ "OK"), "OK", (+ status)] }
+        );
+        assert_eq!(
+            resp! { {"status" => (+ "OK")} }.get_key(&"status".into()),
+            Some(&RespValue::SimpleString("OK".into()))
+        );
+    }
+
     #[test]
     fn string() {
         assert_eq!(RespValue::String("1234".into()), resp! { "1234" });
@@ -236,6 +795,135 @@ mod tests {
         assert_eq!(value.text(), None);
     }
 
+    #[test]
+    fn collect_array() {
+        let value: RespValue = vec![1i64, 2, 3].into_iter().collect();
+        assert_eq!(value, resp! { [1, 2, 3] });
+
+        let value: RespValue = Vec::<i64>::new().into_iter().collect();
+        assert_eq!(value, resp! { [] });
+    }
+
+    #[test]
+    fn collect_map() {
+        let value: RespValue = vec![("a", 1i64), ("b", 2)].into_iter().collect();
+        assert_eq!(value, resp! { {"a" => 1, "b" => 2} });
+    }
+
+    #[tokio::test]
+    async fn byte_size() -> Result<(), crate::RespError> {
+        let values = [
+            RespValue::Integer(0),
+            RespValue::Integer(-1234),
+            RespValue::Integer(i64::MIN),
+            RespValue::Nil,
+            RespValue::String("".into()),
+            RespValue::String("0123456789".into()),
+            RespValue::SimpleString("OK".into()),
+            RespValue::Unknown(b'@', "x y".into()),
+            RespValue::Boolean(true),
+            RespValue::Double(1.5f64.into()),
+            RespValue::Bignum("12345".into()),
+            RespValue::Error("ERR x".into()),
+            RespValue::Error("ERR a\r\nb\nc".into()),
+            resp! { (= "txt", "abc") },
+            resp! { [1, "x", nil, [> true]] },
+            resp! { {"a" => 1, nil => {"b" => [1.5f64]}} },
+            resp! { {"x", 1, nil} },
+        ];
+
+        for version in [RespVersion::V2, RespVersion::V3] {
+            for value in &values {
+                let mut output = Vec::new();
+                let mut writer = RespWriter::new(&mut output);
+                writer.set_version(version);
+                writer.write_value(value).await?;
+                drop(writer);
+                assert_eq!(value.byte_size(version), output.len(), "{:?}", value);
+            }
+        }
+
+        let value = resp! { {a "ttl" => 1} };
+        assert_eq!(value.byte_size(RespVersion::V3), 17);
+        Ok(())
+    }
+
+    #[test]
+    fn frame_count() {
+        assert_eq!(RespValue::Integer(1).frame_count(), 1);
+        assert_eq!(RespValue::Nil.frame_count(), 1);
+        assert_eq!(RespValue::String("x".into()).frame_count(), 1);
+        assert_eq!(resp! { [] }.frame_count(), 1);
+        assert_eq!(resp! { [1, "x", nil] }.frame_count(), 4);
+        assert_eq!(resp! { [> 1, [2, 3]] }.frame_count(), 5);
+        assert_eq!(resp! { {"x", "y"} }.frame_count(), 3);
+        assert_eq!(
+            resp! { {"a" => 1, "b" => {"c" => [1, 2]}} }.frame_count(),
+            9
+        );
+        assert_eq!(resp! { {a "a" => 1} }.frame_count(), 3);
+    }
+
+    #[test]
+    fn approx_eq() {
+        let a = resp! { [(1.1f64 + 2.2f64), {"x" => [3.3f64]}] };
+        let b = resp! { [3.3f64, {"x" => [3.3000001f64]}] };
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, 1e-6));
+        assert!(!a.approx_eq(&b, 1e-9));
+
+        let a = resp! { [> 1.0f64, "x"] };
+        assert!(a.approx_eq(&resp! { [> 1.0f64, "x"] }, 0.0));
+        assert!(!a.approx_eq(&resp! { [> 1.0f64, "y"] }, 1.0));
+        assert!(!a.approx_eq(&resp! { [> 1.0f64] }, 1.0));
+        assert!(!a.approx_eq(&resp! { [1.0f64, "x"] }, 1.0));
+
+        let a = resp! { {"x" => 1.0f64} };
+        assert!(!a.approx_eq(&resp! { {"y" => 1.0f64} }, 1.0));
+
+        let a = RespValue::Double(f64::NAN.into());
+        assert!(a.approx_eq(&a, 0.0));
+    }
+
+    #[test]
+    fn bool_lossy_values() {
+        let cases = [
+            (RespValue::Boolean(true), Some(true)),
+            (RespValue::Boolean(false), Some(false)),
+            (RespValue::Integer(1), Some(true)),
+            (RespValue::Integer(0), Some(false)),
+            (RespValue::Integer(2), None),
+            (RespValue::String("OK".into()), Some(true)),
+            (RespValue::SimpleString("OK".into()), Some(true)),
+            (RespValue::String("true".into()), Some(true)),
+            (RespValue::String("false".into()), Some(false)),
+            (RespValue::String("yes".into()), None),
+            (RespValue::Nil, None),
+            (RespValue::Error("OK".into()), None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(value.as_bool_lossy(), expected, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn verbatim_lossy_values() {
+        let value = RespValue::String("txt:abc".into());
+        assert_eq!(value.as_verbatim_lossy(), Some(resp! { (= "txt", "abc") }));
+
+        let value = RespValue::Verbatim("mkd".into(), "abc".into());
+        assert_eq!(value.as_verbatim_lossy(), Some(resp! { (= "mkd", "abc") }));
+
+        let value = RespValue::String("abc".into());
+        assert_eq!(value.as_verbatim_lossy(), None);
+
+        let value = RespValue::String("te:abc".into());
+        assert_eq!(value.as_verbatim_lossy(), None);
+
+        let value = RespValue::Integer(23);
+        assert_eq!(value.as_verbatim_lossy(), None);
+    }
+
     #[test]
     fn error_values() {
         let value = RespValue::Verbatim("txt".into(), "abc".into());
@@ -272,6 +960,103 @@ mod tests {
         assert_eq!(value.integer(), None);
     }
 
+    #[test]
+    fn from_primitive() -> Result<(), RespError> {
+        for (primitive, value) in [
+            (RespPrimitive::Integer(5), RespValue::Integer(5)),
+            (RespPrimitive::Nil, RespValue::Nil),
+            (
+                RespPrimitive::String("a".into()),
+                RespValue::String("a".into()),
+            ),
+        ] {
+            assert_eq!(RespValue::from(primitive.clone()), value);
+            assert_eq!(RespPrimitive::try_from(value)?, primitive);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn redis_cli_format() {
+        let cases = [
+            (resp! { 5 }, "(integer) 5\n"),
+            (resp! { nil }, "(nil)\n"),
+            (resp! { (! "ERR no") }, "(error) ERR no\n"),
+            (resp! { (+ "OK") }, "OK\n"),
+            (resp! { "a \"b\"\r\n\x01" }, "\"a \\\"b\\\"\\r\\n\\x01\"\n"),
+            (resp! { true }, "(true)\n"),
+            (resp! { 1.5f64 }, "(double) 1.5\n"),
+            (resp! { (big "123") }, "(big number) 123\n"),
+            (resp! { (= "txt", "some text") }, "some text\n"),
+            (resp! { [] }, "(empty array)\n"),
+            (resp! { {} }, "(empty hash)\n"),
+            (resp! { {~} }, "(empty set)\n"),
+            (
+                resp! { [1, [2, "x"], []] },
+                "1) (integer) 1\n2) 1) (integer) 2\n   2) \"x\"\n3) (empty array)\n",
+            ),
+            (
+                resp! { {"a" => 1, "b" => ["x", "y"]} },
+                "1# \"a\" => (integer) 1\n2# \"b\" => 1) \"x\"\n   2) \"y\"\n",
+            ),
+            (resp! { {"a", "b"} }, "1~ \"a\"\n2~ \"b\"\n"),
+            (resp! { [> "message", "x"] }, "1) \"message\"\n2) \"x\"\n"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(value.redis_cli_format(0), expected);
+        }
+
+        let value: RespValue = (1..=10).map(RespValue::Integer).collect();
+        let output = value.redis_cli_format(0);
+        assert!(output.starts_with(" 1) (integer) 1\n 2) (integer) 2\n"));
+        assert!(output.ends_with("10) (integer) 10\n"));
+
+        let value = resp! { ["a", "b"] };
+        assert_eq!(value.redis_cli_format(2), "1) \"a\"\n  2) \"b\"\n");
+    }
+
+    #[test]
+    fn flatten_map() {
+        assert_eq!(resp! { {"a" => 1} }.flatten_map(), resp! { ["a", 1] });
+        assert_eq!(
+            resp! { {"a" => {"b" => [{1 => nil}]}, nil => "c"} }.flatten_map(),
+            resp! { [nil, "c", "a", ["b", [[1, nil]]]] }
+        );
+        assert_eq!(resp! { {a "ttl" => 3} }.flatten_map(), resp! { ["ttl", 3] });
+        assert_eq!(
+            resp! { [> "message", {"a" => 1}] }.flatten_map(),
+            resp! { [> "message", ["a", 1]] }
+        );
+        assert_eq!(resp! { {"a"} }.flatten_map(), resp! { {"a"} });
+        assert_eq!(resp! { {} }.flatten_map(), resp! { [] });
+    }
+
+    #[test]
+    fn into_string_map() -> Result<(), RespError> {
+        let map = resp! { {"a" => 1, "b" => [nil]} }.into_string_map()?;
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Bytes::from("a"), RespValue::Integer(1)),
+                (Bytes::from("b"), resp! { [nil] }),
+            ]
+        );
+
+        assert!(matches!(
+            resp! { {"a" => 1, 2 => 3} }.into_string_map(),
+            Err(RespError::ExpectedString)
+        ));
+        assert!(matches!(
+            resp! { {nil => 1} }.into_string_map(),
+            Err(RespError::ExpectedString)
+        ));
+        assert!(matches!(
+            resp! { [1, 2] }.into_string_map(),
+            Err(RespError::ExpectedMap)
+        ));
+        Ok(())
+    }
+
     #[test]
     fn array_values() {
         let mut value = RespValue::Verbatim("txt".into(), "abc".into());
@@ -295,4 +1080,181 @@ mod tests {
             Some(&mut vec![RespValue::Integer(1), RespValue::Integer(2)])
         );
     }
+
+    #[test]
+    fn get() {
+        let value = resp! { [1, "a", [2]] };
+        assert_eq!(value.get(0), Some(&resp! { 1 }));
+        assert_eq!(
+            value.get(2).and_then(|value| value.get(0)),
+            Some(&resp! { 2 })
+        );
+        assert_eq!(value.get(3), None);
+        assert_eq!(resp! { [> "message", "hi"] }.get(1), Some(&resp! { "hi" }));
+        assert_eq!(resp! { {"a" => 1} }.get(0), None);
+        assert_eq!(RespValue::Integer(1).get(0), None);
+        assert_eq!(RespValue::Nil.get(0), None);
+    }
+
+    #[test]
+    fn get_key() {
+        let value = resp! { {"a" => 1, 2 => [3]} };
+        assert_eq!(value.get_key(&"a".into()), Some(&resp! { 1 }));
+        assert_eq!(value.get_key(&2.into()), Some(&resp! { [3] }));
+        assert_eq!(value.get_key(&"b".into()), None);
+        assert_eq!(value.get_key(&RespPrimitive::Nil), None);
+        let value = resp! { {a "ttl" => 3} };
+        assert_eq!(value.get_key(&"ttl".into()), Some(&resp! { 3 }));
+        assert_eq!(resp! { [1] }.get_key(&"a".into()), None);
+        assert_eq!(RespValue::String("a".into()).get_key(&"a".into()), None);
+    }
+
+    #[test]
+    fn map_from_pairs() -> Result<(), RespError> {
+        assert_eq!(
+            RespValue::map_from_pairs([("a", 1), ("b", 2)])?,
+            resp! { {"a" => 1, "b" => 2} }
+        );
+        assert_eq!(
+            RespValue::map_from_pairs([
+                (resp! { 1 }, resp! { [nil] }),
+                (resp! { nil }, resp! { "x" })
+            ])?,
+            resp! { {1 => [nil], nil => "x"} }
+        );
+        assert_eq!(
+            RespValue::map_from_pairs(Vec::<(RespValue, RespValue)>::new())?,
+            resp! { {} }
+        );
+        assert!(matches!(
+            RespValue::map_from_pairs([("a", 1), ("a", 2)]),
+            Err(RespError::InvalidMap)
+        ));
+        assert!(matches!(
+            RespValue::map_from_pairs([(resp! { [1] }, 1)]),
+            Err(RespError::RespPrimitive)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn set_from() -> Result<(), RespError> {
+        assert_eq!(RespValue::set_from(["a", "b"])?, resp! { {"a", "b"} });
+        assert_eq!(
+            RespValue::set_from([resp! { 1 }, resp! { "a" }, resp! { nil }])?,
+            resp! { {1, "a", nil} }
+        );
+        assert!(matches!(
+            RespValue::set_from(["a", "a"]),
+            Err(RespError::InvalidSet)
+        ));
+        assert!(matches!(
+            RespValue::set_from([resp! { 1 }, resp! { {} }]),
+            Err(RespError::RespPrimitive)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn push_kind() {
+        let value = resp! { [> "message", "channel", "hi"] };
+        assert_eq!(value.push_kind(), Some(&b"message"[..]));
+        let value = resp! { [> (+ "subscribe"), "channel", 1] };
+        assert_eq!(value.push_kind(), Some(&b"subscribe"[..]));
+        assert_eq!(resp! { [> 1, "channel"] }.push_kind(), None);
+        assert_eq!(resp! { [> nil] }.push_kind(), None);
+        assert_eq!(resp! { [>] }.push_kind(), None);
+        assert_eq!(resp! { ["message", "channel", "hi"] }.push_kind(), None);
+        assert_eq!(RespValue::String("message".into()).push_kind(), None);
+    }
+
+    #[test]
+    fn depth() {
+        assert_eq!(RespValue::Integer(1).depth(), 1);
+        assert_eq!(RespValue::Nil.depth(), 1);
+        assert_eq!(resp! { [] }.depth(), 1);
+        assert_eq!(resp! { [1, "a", nil] }.depth(), 2);
+        assert_eq!(resp! { [1, [2, [3]], [4]] }.depth(), 4);
+        assert_eq!(resp! { {} }.depth(), 1);
+        assert_eq!(resp! { {"a" => 1} }.depth(), 2);
+        assert_eq!(resp! { {"a" => 1, "b" => [[2]]} }.depth(), 4);
+        assert_eq!(resp! { {a "a" => [1]} }.depth(), 3);
+        assert_eq!(resp! { {~} }.depth(), 1);
+        assert_eq!(resp! { [{1, 2}] }.depth(), 3);
+        assert_eq!(resp! { [> "message", ["a"]] }.depth(), 3);
+
+        let mut value = RespValue::Nil;
+        for _ in 0..100 {
+            value = RespValue::Array(vec![value]);
+        }
+        assert_eq!(value.depth(), 101);
+    }
+
+    #[test]
+    fn try_from_frame() -> Result<(), RespError> {
+        let cases = [
+            (RespFrame::Bignum("1".into()), resp! { (big "1") }),
+            (RespFrame::BlobError("ERR x".into()), resp! { (! "ERR x") }),
+            (
+                RespFrame::BlobString("x".into()),
+                RespValue::String("x".into()),
+            ),
+            (RespFrame::Boolean(true), RespValue::Boolean(true)),
+            (RespFrame::Double(1.5.into()), RespValue::Double(1.5.into())),
+            (RespFrame::Integer(1), RespValue::Integer(1)),
+            (RespFrame::Nil, RespValue::Nil),
+            (
+                RespFrame::SimpleError("ERR y".into()),
+                resp! { (! "ERR y") },
+            ),
+            (RespFrame::SimpleString("OK".into()), resp! { (+ "OK") }),
+            (
+                RespFrame::Unknown(b'@', "x".into()),
+                RespValue::Unknown(b'@', "x".into()),
+            ),
+            (
+                RespFrame::Verbatim("txt".into(), "x".into()),
+                resp! { (= "txt", "x") },
+            ),
+        ];
+        for (frame, expected) in cases {
+            assert_eq!(RespValue::try_from(frame)?, expected);
+        }
+
+        for frame in [
+            RespFrame::Array(1),
+            RespFrame::Attribute(0),
+            RespFrame::Map(2),
+            RespFrame::Push(3),
+            RespFrame::Set(4),
+        ] {
+            assert!(matches!(
+                RespValue::try_from(frame),
+                Err(RespError::ExpectedScalar)
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn eq_ignoring_attributes() {
+        let plain = resp! { [1, ["a", "b"], {"k" => [2]}] };
+        let annotated =
+            resp! { [{a "ttl" => 3}, 1, ["a", {a "x" => 1}, "b"], {"k" => [{a "y" => 0}, 2]}] };
+        assert!(plain.eq_ignoring_attributes(&annotated));
+        assert!(annotated.eq_ignoring_attributes(&plain));
+        assert!(plain.eq_ignoring_attributes(&plain));
+        assert_ne!(plain, annotated);
+
+        let push = resp! { [> "message", {a "x" => 1}, "hi"] };
+        assert!(push.eq_ignoring_attributes(&resp! { [> "message", "hi"] }));
+        assert!(!push.eq_ignoring_attributes(&resp! { ["message", "hi"] }));
+
+        assert!(!plain.eq_ignoring_attributes(&resp! { [1, ["a", "b"]] }));
+        assert!(!plain.eq_ignoring_attributes(&resp! { [1, ["a", "c"], {"k" => [2]}] }));
+        assert!(!resp! { [1] }.eq_ignoring_attributes(&resp! { [1, {a "x" => 1}, 2] }));
+        assert!(resp! { {a "x" => 1} }.eq_ignoring_attributes(&resp! { {a "y" => 0} }));
+        assert!(RespValue::Integer(1).eq_ignoring_attributes(&RespValue::Integer(1)));
+        assert!(!RespValue::Integer(1).eq_ignoring_attributes(&RespValue::Integer(2)));
+    }
 }
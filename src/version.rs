@@ -1,3 +1,6 @@
+use crate::RespError;
+use core::{fmt, str::FromStr};
+
 /// A version of the RESP protocol.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum RespVersion {
@@ -5,8 +8,8 @@ pub enum RespVersion {
     V3,
 }
 
-impl std::fmt::Display for RespVersion {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for RespVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             RespVersion::V2 => write!(f, "2"),
             RespVersion::V3 => write!(f, "3"),
@@ -22,3 +25,64 @@ impl From<RespVersion> for u8 {
         }
     }
 }
+
+impl TryFrom<u8> for RespVersion {
+    type Error = RespError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            2 => Ok(RespVersion::V2),
+            3 => Ok(RespVersion::V3),
+            _ => Err(RespError::InvalidVersion),
+        }
+    }
+}
+
+impl FromStr for RespVersion {
+    type Err = RespError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "2" => Ok(RespVersion::V2),
+            "3" => Ok(RespVersion::V3),
+            _ => Err(RespError::InvalidVersion),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_from_u8() {
+        assert_eq!(RespVersion::try_from(2).ok(), Some(RespVersion::V2));
+        assert_eq!(RespVersion::try_from(3).ok(), Some(RespVersion::V3));
+        for value in [0, 1, 4, 255] {
+            assert!(matches!(
+                RespVersion::try_from(value),
+                Err(RespError::InvalidVersion)
+            ));
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("2".parse().ok(), Some(RespVersion::V2));
+        assert_eq!("3".parse().ok(), Some(RespVersion::V3));
+        for value in ["", "1", "4", "03", " 3", "v3", "three"] {
+            assert!(matches!(
+                value.parse::<RespVersion>(),
+                Err(RespError::InvalidVersion)
+            ));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for version in [RespVersion::V2, RespVersion::V3] {
+            assert_eq!(RespVersion::try_from(u8::from(version)).ok(), Some(version));
+            assert_eq!(version.to_string().parse().ok(), Some(version));
+        }
+    }
+}
@@ -1,5 +1,11 @@
-use crate::{RespError, RespVersion};
-use std::io::Write;
+use crate::{RespError, RespFrame, RespFrameRef, RespPrimitive, RespValue, RespVersion};
+use bytes::BufMut;
+use std::{
+    borrow::Borrow,
+    cmp, fmt,
+    io::{self, IoSlice, Write},
+    mem,
+};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 /// A wrapper for [`AsyncWrite`] to allow writing a RESP stream.
@@ -8,24 +14,49 @@ pub struct RespWriter<Inner: AsyncWrite + Unpin> {
     /// A buffer for writing output
     buffer: Vec<u8>,
 
+    /// The buffer is drained to `inner` after each frame once it exceeds this size.
+    buffer_threshold: usize,
+
     /// The inner `AsyncWrite`.
     inner: Inner,
 
     /// The current version.
-    pub version: RespVersion,
+    version: RespVersion,
+
+    /// Whether doubles are written as blob strings in V2.
+    blob_doubles: bool,
+
+    /// The maximum nesting depth of values written with [`RespWriter::write_value`].
+    depth_limit: usize,
+
+    /// Frames remaining in each open aggregate, if frame checks are enabled.
+    remaining: Option<Vec<usize>>,
 }
 
+/// Values at least this large are written directly instead of being copied into the buffer.
+const DIRECT_WRITE_SIZE: usize = 64 * 1024;
+
 macro_rules! write_all {
     ($self:expr, $value:expr) => {{
-        $self.inner.write_all($value).await?;
+        $self.buffer.extend_from_slice($value);
+    }};
+}
+
+macro_rules! write_line {
+    ($self:expr, $value:expr) => {{
+        let value: &[u8] = $value;
+        if value.len() >= DIRECT_WRITE_SIZE {
+            $self.drain_with(value).await?;
+        } else {
+            $self.buffer.extend_from_slice(value);
+            $self.buffer.extend_from_slice(b"\r\n");
+        }
     }};
 }
 
 macro_rules! write_fmt {
     ($self:expr, $($tail:tt)*) => {{
-        $self.buffer.clear();
         write!($self.buffer, $( $tail )*).unwrap();
-        write_all!($self, &$self.buffer[..]);
     }};
 }
 
@@ -34,11 +65,81 @@ impl<Inner: AsyncWrite + Unpin> RespWriter<Inner> {
     pub fn new(inner: Inner) -> Self {
         Self {
             buffer: Vec::new(),
+            buffer_threshold: 0,
             inner,
             version: RespVersion::V2,
+            blob_doubles: false,
+            depth_limit: 128,
+            remaining: None,
         }
     }
 
+    /// The current version.
+    pub fn version(&self) -> RespVersion {
+        self.version
+    }
+
+    /// Set the version to write.
+    ///
+    /// The default is [`RespVersion::V2`].
+    pub fn set_version(&mut self, version: RespVersion) {
+        self.version = version;
+    }
+
+    /// Set the buffer threshold.
+    ///
+    /// Output is buffered until it exceeds this many bytes, or until [`RespWriter::flush`] is
+    /// called. The default is zero, which writes each frame to the inner writer as soon as it's
+    /// complete.
+    pub fn set_buffer_threshold(&mut self, value: usize) {
+        self.buffer_threshold = value;
+    }
+
+    /// Set whether doubles are written as blob strings in V2, instead of simple strings.
+    ///
+    /// Redis itself replies with blob strings for doubles in RESP2, such as for `ZSCORE` and
+    /// `INCRBYFLOAT`, so clients may expect them. The default is `false`.
+    pub fn set_blob_doubles(&mut self, value: bool) {
+        self.blob_doubles = value;
+    }
+
+    /// Set the maximum nesting depth of values written with [`RespWriter::write_value`].
+    ///
+    /// Scalars have a depth of one, and each aggregate adds one to the depth of its children.
+    /// The default is 128.
+    pub fn set_depth_limit(&mut self, value: usize) {
+        self.depth_limit = value;
+    }
+
+    /// Enable or disable frame checks.
+    ///
+    /// When enabled, the writer tracks how many frames each aggregate header promises, and
+    /// [`RespWriter::flush`] returns [`RespError::FrameCount`] if any aggregate is incomplete.
+    /// This is meant for catching bugs in tests.
+    pub fn set_frame_checks(&mut self, enabled: bool) {
+        self.remaining = enabled.then(Vec::new);
+    }
+
+    /// Reuse this writer for a new stream, keeping its settings and the buffer's capacity.
+    ///
+    /// Any output that hasn't been flushed is discarded, and the version is reset to
+    /// [`RespVersion::V2`].
+    pub fn reset(&mut self, inner: Inner) {
+        self.buffer.clear();
+        self.inner = inner;
+        if let Some(remaining) = &mut self.remaining {
+            remaining.clear();
+        }
+        self.version = RespVersion::V2;
+    }
+
+    /// Unwrap the inner [`AsyncWrite`].
+    ///
+    /// Any output that hasn't been flushed is discarded, so call [`RespWriter::flush`] first.
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
     /// Write an inline command.
     pub async fn write_inline(&mut self, value: &[u8]) -> Result<(), RespError> {
         if value.first() == Some(&b'*') {
@@ -47,27 +148,33 @@ impl<Inner: AsyncWrite + Unpin> RespWriter<Inner> {
         if value.iter().any(|&b| b == b'\r' || b == b'\n') {
             return Err(RespError::Newline);
         }
-        write_all!(self, value);
-        write_all!(self, b"\r\n");
-        Ok(())
+        write_line!(self, value);
+        self.drain_full().await
     }
 
-    /// Flush the inner writer.
+    /// Flush all buffered output and the inner writer.
     pub async fn flush(&mut self) -> Result<(), RespError> {
+        if let Some(remaining) = &mut self.remaining {
+            if !remaining.is_empty() {
+                remaining.clear();
+                return Err(RespError::FrameCount);
+            }
+        }
+        self.drain().await?;
         self.inner.flush().await?;
         Ok(())
     }
 
     /// Shut down the inner writer.
     pub async fn shutdown(&mut self) -> Result<(), RespError> {
+        self.drain().await?;
         self.inner.shutdown().await?;
         Ok(())
     }
 
     /// Write an array frame.
     pub async fn write_array(&mut self, len: usize) -> Result<(), RespError> {
-        write_fmt!(self, "*{}\r\n", len);
-        Ok(())
+        self.write_frame_ref(RespFrameRef::Array(len)).await
     }
 
     /// Write an attribute frame.
@@ -76,145 +183,355 @@ impl<Inner: AsyncWrite + Unpin> RespWriter<Inner> {
             return Err(RespError::Version);
         }
         write_fmt!(self, "|{}\r\n", value.len());
-        write_all!(self, value);
-        write_all!(self, b"\r\n");
-        Ok(())
+        write_line!(self, value);
+        self.frame(0).await
     }
 
     /// Write a bignum frame.
     pub async fn write_bignum(&mut self, value: &[u8]) -> Result<(), RespError> {
-        if value.contains(&b'\n') {
-            return Err(RespError::Newline);
-        }
-        match self.v3() {
-            true => write_all!(self, b"("),
-            false => write_all!(self, b"+"),
-        }
-        write_all!(self, value);
-        write_all!(self, b"\r\n");
-        Ok(())
+        self.write_frame_ref(RespFrameRef::Bignum(value)).await
     }
 
     /// Write a blob error frame.
     pub async fn write_blob_error(&mut self, value: &[u8]) -> Result<(), RespError> {
-        if self.v2() {
-            return Err(RespError::Version);
+        self.write_frame_ref(RespFrameRef::BlobError(value)).await
+    }
+
+    /// Write an error, even if it contains newlines.
+    ///
+    /// In V3, a message with newlines is written as a blob error. In V2, where there are no blob
+    /// errors, each `\r` and `\n` is replaced by a space in a simple error.
+    pub async fn write_error(&mut self, value: &[u8]) -> Result<(), RespError> {
+        if !value.iter().any(|&b| b == b'\r' || b == b'\n') {
+            return self.write_simple_error(value).await;
         }
-        write_fmt!(self, "!{}\r\n", value.len());
-        write_all!(self, value);
-        write_all!(self, b"\r\n");
-        Ok(())
+
+        if self.v3() {
+            return self.write_blob_error(value).await;
+        }
+
+        let value: Vec<u8> = value
+            .iter()
+            .map(|&b| if b == b'\r' || b == b'\n' { b' ' } else { b })
+            .collect();
+        self.write_simple_error(&value).await
     }
 
     /// Write a blob string frame.
     pub async fn write_blob_string(&mut self, value: &[u8]) -> Result<(), RespError> {
-        write_fmt!(self, "${}\r\n", value.len());
-        write_all!(self, value);
-        write_all!(self, b"\r\n");
-        Ok(())
+        self.write_frame_ref(RespFrameRef::BlobString(value)).await
     }
 
     /// Write a boolean frame.
     pub async fn write_boolean(&mut self, value: bool) -> Result<(), RespError> {
-        let bytes = match (self.v3(), value) {
-            (true, true) => b"#t\r\n",
-            (true, false) => b"#f\r\n",
-            (false, true) => b":1\r\n",
-            (false, false) => b":0\r\n",
-        };
-        write_all!(self, bytes);
-        Ok(())
+        self.write_frame_ref(RespFrameRef::Boolean(value)).await
     }
 
     /// Write a double frame.
+    ///
+    /// Infinities and NaN are written as `inf`, `-inf`, and `nan`. In V2, doubles are written as
+    /// simple strings, or as blob strings if [`RespWriter::set_blob_doubles`] is enabled.
     pub async fn write_double(&mut self, value: f64) -> Result<(), RespError> {
-        match self.v3() {
-            true => write_fmt!(self, ",{}\r\n", value),
-            false => write_fmt!(self, "+{}\r\n", value),
-        }
-        Ok(())
+        self.write_frame_ref(RespFrameRef::Double(value.into()))
+            .await
     }
 
     /// Write an integer frame.
     pub async fn write_integer(&mut self, value: i64) -> Result<(), RespError> {
-        write_fmt!(self, ":{}\r\n", value);
-        Ok(())
+        self.write_frame_ref(RespFrameRef::Integer(value)).await
     }
 
     /// Write a nil frame.
     pub async fn write_nil(&mut self) -> Result<(), RespError> {
+        self.write_frame_ref(RespFrameRef::Nil).await
+    }
+
+    /// Write an array header, returning an [`ArrayWriter`] that checks its element count.
+    ///
+    /// ```
+    /// # use tokio::runtime::Runtime;
+    /// # use respite::{RespError, RespWriter};
+    /// # let runtime = Runtime::new().unwrap();
+    /// # runtime.block_on(async {
+    /// let mut output = Vec::new();
+    /// let mut writer = RespWriter::new(&mut output);
+    /// let mut array = writer.array_scope(2).await?;
+    /// array.write_blob_string(b"a").await?;
+    /// array.write_integer(1).await?;
+    /// array.finish()?;
+    /// drop(writer);
+    /// assert_eq!(&output[..], b"*2\r\n$1\r\na\r\n:1\r\n");
+    /// # Ok::<(), RespError>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn array_scope(&mut self, len: usize) -> Result<ArrayWriter<'_, Inner>, RespError> {
+        self.write_array(len).await?;
+        Ok(ArrayWriter {
+            writer: self,
+            len,
+            written: 0,
+        })
+    }
+
+    /// Write a null array frame.
+    ///
+    /// In V2 this is `*-1`, which some clients expect instead of a null blob string, either as a
+    /// reply or as an element within an array. In V3 it's the same as [`RespWriter::write_nil`].
+    pub async fn write_null_array(&mut self) -> Result<(), RespError> {
+        match self.v3() {
+            true => write_all!(self, b"_\r\n"),
+            false => write_all!(self, b"*-1\r\n"),
+        }
+        self.frame(0).await
+    }
+
+    /// Write a null blob string frame.
+    ///
+    /// In V2 this is `$-1`, the legacy null for bulk replies. In V3 it's the same as
+    /// [`RespWriter::write_nil`].
+    pub async fn write_null_string(&mut self) -> Result<(), RespError> {
         match self.v3() {
             true => write_all!(self, b"_\r\n"),
             false => write_all!(self, b"$-1\r\n"),
         }
-        Ok(())
+        self.frame(0).await
     }
 
     /// Write a map frame.
     pub async fn write_map(&mut self, len: usize) -> Result<(), RespError> {
-        match self.v3() {
-            true => write_fmt!(self, "%{}\r\n", len),
-            false => write_fmt!(self, "*{}\r\n", 2 * len),
+        self.write_frame_ref(RespFrameRef::Map(len)).await
+    }
+
+    /// Write a map header followed by each of `entries`.
+    ///
+    /// In V2, this is an array of alternating keys and values.
+    pub async fn write_map_entries<I>(&mut self, entries: I) -> Result<(), RespError>
+    where
+        I: IntoIterator<Item = (RespValue, RespValue)>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let entries = entries.into_iter();
+        self.write_map(entries.len()).await?;
+        for (key, value) in entries {
+            self.write_value(&key).await?;
+            self.write_value(&value).await?;
         }
         Ok(())
     }
 
     /// Write a push frame.
     pub async fn write_push(&mut self, len: usize) -> Result<(), RespError> {
-        match self.v3() {
-            true => write_fmt!(self, ">{}\r\n", len),
-            false => write_fmt!(self, "*{}\r\n", len),
-        }
-        Ok(())
+        self.write_frame_ref(RespFrameRef::Push(len)).await
     }
 
     /// Write a set frame.
     pub async fn write_set(&mut self, len: usize) -> Result<(), RespError> {
-        match self.v3() {
-            true => write_fmt!(self, "~{}\r\n", len),
-            false => write_fmt!(self, "*{}\r\n", len),
-        }
-        Ok(())
+        self.write_frame_ref(RespFrameRef::Set(len)).await
     }
 
     /// Write a simple error frame.
     pub async fn write_simple_error(&mut self, value: &[u8]) -> Result<(), RespError> {
-        if value.iter().any(|&b| b == b'\r' || b == b'\n') {
-            return Err(RespError::Newline);
-        }
-        write_all!(self, b"-");
-        write_all!(self, value);
-        write_all!(self, b"\r\n");
-        Ok(())
+        self.write_frame_ref(RespFrameRef::SimpleError(value)).await
+    }
+
+    /// Write a frame of an unknown type, as a type byte followed by a line.
+    ///
+    /// This is for passing through frames read with [`RespConfig::unknown_types`] enabled.
+    ///
+    /// [`RespConfig::unknown_types`]: crate::RespConfig::unknown_types
+    pub async fn write_unknown(&mut self, kind: u8, value: &[u8]) -> Result<(), RespError> {
+        self.write_frame_ref(RespFrameRef::Unknown(kind, value))
+            .await
     }
 
     /// Write a simple string frame.
     pub async fn write_simple_string(&mut self, value: &[u8]) -> Result<(), RespError> {
-        if value.iter().any(|&b| b == b'\r' || b == b'\n') {
-            return Err(RespError::Newline);
-        }
-        write_all!(self, b"+");
-        write_all!(self, value);
-        write_all!(self, b"\r\n");
-        Ok(())
+        self.write_frame_ref(RespFrameRef::SimpleString(value))
+            .await
     }
 
     /// Write a verbatim frame.
     pub async fn write_verbatim(&mut self, format: &[u8], value: &[u8]) -> Result<(), RespError> {
-        if self.v3() {
-            write_fmt!(self, "={}\r\n", format.len() + 1 + value.len());
-            write_all!(self, format);
-            write_all!(self, b":");
-            write_all!(self, value);
-            write_all!(self, b"\r\n");
-        } else {
-            write_fmt!(self, "${}\r\n", value.len());
-            write_all!(self, value);
-            write_all!(self, b"\r\n");
+        self.write_frame_ref(RespFrameRef::Verbatim(format, value))
+            .await
+    }
+
+    /// Record one frame, which is followed by `len` child frames, and drain the buffer if full.
+    async fn frame(&mut self, len: usize) -> Result<(), RespError> {
+        if let Some(remaining) = &mut self.remaining {
+            if let Some(last) = remaining.last_mut() {
+                *last -= 1;
+            }
+
+            remaining.push(len);
+
+            while remaining.last() == Some(&0) {
+                remaining.pop();
+            }
+        }
+
+        self.drain_full().await
+    }
+
+    /// Drain the buffer if it exceeds the threshold.
+    async fn drain_full(&mut self) -> Result<(), RespError> {
+        if self.buffer.len() > self.buffer_threshold {
+            self.drain().await?;
+        }
+        Ok(())
+    }
+
+    /// Write the whole buffer to the inner writer, followed by `value` and a CRLF.
+    ///
+    /// This uses a vectored write to avoid copying `value` into the buffer.
+    async fn drain_with(&mut self, value: &[u8]) -> Result<(), RespError> {
+        let mut parts = [&self.buffer[..], value, b"\r\n"];
+        while parts.iter().any(|part| !part.is_empty()) {
+            let slices = parts.map(IoSlice::new);
+            let mut n = self.inner.write_vectored(&slices).await?;
+            if n == 0 {
+                return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+            }
+            for part in &mut parts {
+                let len = cmp::min(n, part.len());
+                *part = &part[len..];
+                n -= len;
+            }
         }
+        self.buffer.clear();
         Ok(())
     }
 
+    /// Write the whole buffer to the inner writer.
+    async fn drain(&mut self) -> Result<(), RespError> {
+        if !self.buffer.is_empty() {
+            self.inner.write_all(&self.buffer).await?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Write a single frame, with any trailing line of data written directly if it's large.
+    async fn write_frame_ref(&mut self, frame: RespFrameRef<'_>) -> Result<(), RespError> {
+        let (data, len) = encode_head(&mut self.buffer, frame, self.version, self.blob_doubles)?;
+        if let Some(value) = data {
+            write_line!(self, value);
+        }
+        self.frame(len).await
+    }
+
+    /// Write a single [`RespFrame`].
+    ///
+    /// Aggregate frames only write a header, and must be followed by their elements.
+    pub async fn write_frame(&mut self, frame: &RespFrame) -> Result<(), RespError> {
+        self.write_frame_ref(frame.as_frame_ref()).await
+    }
+
+    /// Write a whole [`RespValue`], including all of its children.
+    ///
+    /// Returns [`RespError::DepthLimit`] without writing anything if the value is nested too
+    /// deeply.
+    pub async fn write_value(&mut self, value: &RespValue) -> Result<(), RespError> {
+        if exceeds_depth(value, self.depth_limit) {
+            return Err(RespError::DepthLimit);
+        }
+        self.write_value_inner(value).await
+    }
+
+    /// Write several whole values, such as replies to a pipeline of requests, then flush.
+    ///
+    /// Output is buffered until all values have been written, regardless of the buffer
+    /// threshold, so that they can be written to the inner writer together.
+    pub async fn write_values<I>(&mut self, values: I) -> Result<(), RespError>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<RespValue>,
+    {
+        let threshold = mem::replace(&mut self.buffer_threshold, usize::MAX);
+        let mut result = Ok(());
+        for value in values {
+            result = self.write_value(value.borrow()).await;
+            if result.is_err() {
+                break;
+            }
+        }
+        self.buffer_threshold = threshold;
+        result?;
+        self.flush().await
+    }
+
+    /// Write a [`RespValue`] that has already been checked against the depth limit.
+    async fn write_value_inner(&mut self, value: &RespValue) -> Result<(), RespError> {
+        use RespValue::*;
+
+        match value {
+            Attribute(map) => {
+                if self.v2() {
+                    return Err(RespError::Version);
+                }
+                write_fmt!(self, "|{}\r\n", map.len());
+                self.frame(2 * map.len()).await?;
+                for (key, value) in map {
+                    self.write_primitive_inner(key).await?;
+                    Box::pin(self.write_value_inner(value)).await?;
+                }
+            }
+            Array(values) => {
+                self.write_array(values.len()).await?;
+                for value in values {
+                    Box::pin(self.write_value_inner(value)).await?;
+                }
+            }
+            Bignum(value) => self.write_bignum(value).await?,
+            Boolean(value) => self.write_boolean(*value).await?,
+            Double(value) => self.write_double(value.0).await?,
+            Error(value) => self.write_error(value).await?,
+            Integer(value) => self.write_integer(*value).await?,
+            Map(map) => {
+                self.write_map(map.len()).await?;
+                for (key, value) in map {
+                    self.write_primitive_inner(key).await?;
+                    Box::pin(self.write_value_inner(value)).await?;
+                }
+            }
+            Nil => self.write_nil().await?,
+            Push(values) => {
+                self.write_push(values.len()).await?;
+                for value in values {
+                    Box::pin(self.write_value_inner(value)).await?;
+                }
+            }
+            Set(set) => {
+                self.write_set(set.len()).await?;
+                for value in set {
+                    self.write_primitive_inner(value).await?;
+                }
+            }
+            SimpleString(value) => self.write_simple_string(value).await?,
+            String(value) => self.write_blob_string(value).await?,
+            Unknown(kind, value) => self.write_unknown(*kind, value).await?,
+            Verbatim(format, value) => self.write_verbatim(format, value).await?,
+        }
+        Ok(())
+    }
+
+    /// Write a [`RespPrimitive`].
+    pub async fn write_primitive(&mut self, value: &RespPrimitive) -> Result<(), RespError> {
+        if self.depth_limit == 0 {
+            return Err(RespError::DepthLimit);
+        }
+        self.write_primitive_inner(value).await
+    }
+
+    /// Write a [`RespPrimitive`] without checking the depth limit.
+    async fn write_primitive_inner(&mut self, value: &RespPrimitive) -> Result<(), RespError> {
+        match value {
+            RespPrimitive::Integer(value) => self.write_integer(*value).await,
+            RespPrimitive::Nil => self.write_nil().await,
+            RespPrimitive::String(value) => self.write_blob_string(value).await,
+        }
+    }
+
     /// Is the current version V2?
     fn v2(&self) -> bool {
         self.version == RespVersion::V2
@@ -226,16 +543,292 @@ impl<Inner: AsyncWrite + Unpin> RespWriter<Inner> {
     }
 }
 
+/// Writes the elements of an array, checking that exactly the declared number are written.
+///
+/// Created by [`RespWriter::array_scope`]. Writing too many elements returns
+/// [`RespError::FrameCount`] without writing anything. Writing too few makes
+/// [`ArrayWriter::finish`] return [`RespError::FrameCount`]. An element is only counted once
+/// it's written successfully.
+#[derive(Debug)]
+pub struct ArrayWriter<'a, Inner: AsyncWrite + Unpin> {
+    /// The underlying writer.
+    writer: &'a mut RespWriter<Inner>,
+
+    /// The declared number of elements.
+    len: usize,
+
+    /// The number of elements written so far.
+    written: usize,
+}
+
+impl<Inner: AsyncWrite + Unpin> ArrayWriter<'_, Inner> {
+    /// Count one more element, returning the writer to write it with.
+    ///
+    /// The caller must write exactly one value, which may be an aggregate with its children. The
+    /// element is counted even if writing it fails.
+    pub fn element(&mut self) -> Result<&mut RespWriter<Inner>, RespError> {
+        self.check()?;
+        self.written += 1;
+        Ok(self.writer)
+    }
+
+    /// Return [`RespError::FrameCount`] if every element has already been written.
+    fn check(&self) -> Result<(), RespError> {
+        if self.written >= self.len {
+            return Err(RespError::FrameCount);
+        }
+        Ok(())
+    }
+
+    /// Write a blob string element.
+    pub async fn write_blob_string(&mut self, value: &[u8]) -> Result<(), RespError> {
+        self.check()?;
+        self.writer.write_blob_string(value).await?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Write an integer element.
+    pub async fn write_integer(&mut self, value: i64) -> Result<(), RespError> {
+        self.check()?;
+        self.writer.write_integer(value).await?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Write a nil element.
+    pub async fn write_nil(&mut self) -> Result<(), RespError> {
+        self.check()?;
+        self.writer.write_nil().await?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Write a simple string element.
+    pub async fn write_simple_string(&mut self, value: &[u8]) -> Result<(), RespError> {
+        self.check()?;
+        self.writer.write_simple_string(value).await?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Write a whole [`RespValue`] element.
+    pub async fn write_value(&mut self, value: &RespValue) -> Result<(), RespError> {
+        self.check()?;
+        self.writer.write_value(value).await?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Finish the array, returning [`RespError::FrameCount`] if any elements are missing.
+    pub fn finish(self) -> Result<(), RespError> {
+        if self.written != self.len {
+            return Err(RespError::FrameCount);
+        }
+        Ok(())
+    }
+}
+
+/// Is `value` nested more than `limit` levels deep?
+///
+/// This walks the value with its own stack, so that values too deep to write can't overflow the
+/// call stack while being checked.
+fn exceeds_depth(value: &RespValue, limit: usize) -> bool {
+    use RespValue::*;
+
+    let mut stack = vec![(value, 1)];
+    while let Some((value, depth)) = stack.pop() {
+        if depth > limit {
+            return true;
+        }
+
+        match value {
+            Array(values) | Push(values) => {
+                stack.extend(values.iter().map(|value| (value, depth + 1)));
+            }
+            Attribute(map) | Map(map) => {
+                // Keys are primitives, one level down.
+                if !map.is_empty() && depth == limit {
+                    return true;
+                }
+                stack.extend(map.values().map(|value| (value, depth + 1)));
+            }
+            Set(set) if !set.is_empty() && depth == limit => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Encode all of `frame` as it's written in `version` at the end of `output`.
+#[cfg(feature = "codec")]
+pub(crate) fn encode_frame<B: BufMut>(
+    output: &mut B,
+    frame: RespFrameRef<'_>,
+    version: RespVersion,
+) -> Result<(), RespError> {
+    if let (Some(value), _) = encode_head(output, frame, version, false)? {
+        output.put_slice(value);
+        output.put_slice(b"\r\n");
+    }
+    Ok(())
+}
+
+/// Encode `frame` as it's written in `version` at the end of `output`, except for any trailing
+/// line of data.
+///
+/// Returns that data, to be written as a line after the rest of the frame, along with the number
+/// of frames that must follow this one. Nothing is written if the frame can't be encoded.
+fn encode_head<'a, B: BufMut>(
+    output: &mut B,
+    frame: RespFrameRef<'a>,
+    version: RespVersion,
+    blob_doubles: bool,
+) -> Result<(Option<&'a [u8]>, usize), RespError> {
+    use RespFrameRef::*;
+
+    let v3 = version == RespVersion::V3;
+    let newline = |value: &[u8]| value.iter().any(|&b| b == b'\r' || b == b'\n');
+
+    match frame {
+        Array(len) => {
+            put_fmt(output, format_args!("*{}\r\n", len));
+            Ok((None, len))
+        }
+        Attribute(len) => {
+            if !v3 {
+                return Err(RespError::Version);
+            }
+            put_fmt(output, format_args!("|{}\r\n", len));
+            Ok((None, 2 * len))
+        }
+        Bignum(value) => {
+            if value.contains(&b'\n') {
+                return Err(RespError::Newline);
+            }
+            output.put_slice(if v3 { b"(" } else { b"+" });
+            Ok((Some(value), 0))
+        }
+        BlobError(value) => {
+            if !v3 {
+                return Err(RespError::Version);
+            }
+            put_fmt(output, format_args!("!{}\r\n", value.len()));
+            Ok((Some(value), 0))
+        }
+        BlobString(value) => {
+            put_fmt(output, format_args!("${}\r\n", value.len()));
+            Ok((Some(value), 0))
+        }
+        Boolean(value) => {
+            output.put_slice(match (v3, value) {
+                (true, true) => b"#t\r\n",
+                (true, false) => b"#f\r\n",
+                (false, true) => b":1\r\n",
+                (false, false) => b":0\r\n",
+            });
+            Ok((None, 0))
+        }
+        Double(value) if !v3 && blob_doubles => {
+            let mut text = Vec::new();
+            write_double_text(&mut text, value.0);
+            put_fmt(output, format_args!("${}\r\n", text.len()));
+            output.put_slice(&text);
+            output.put_slice(b"\r\n");
+            Ok((None, 0))
+        }
+        Double(value) => {
+            output.put_slice(if v3 { b"," } else { b"+" });
+            write_double_text(output, value.0);
+            output.put_slice(b"\r\n");
+            Ok((None, 0))
+        }
+        Integer(value) => {
+            put_fmt(output, format_args!(":{}\r\n", value));
+            Ok((None, 0))
+        }
+        Map(len) => {
+            match v3 {
+                true => put_fmt(output, format_args!("%{}\r\n", len)),
+                false => put_fmt(output, format_args!("*{}\r\n", 2 * len)),
+            }
+            Ok((None, 2 * len))
+        }
+        Nil => {
+            output.put_slice(if v3 { b"_\r\n" } else { b"$-1\r\n" });
+            Ok((None, 0))
+        }
+        Push(len) => {
+            let kind = if v3 { '>' } else { '*' };
+            put_fmt(output, format_args!("{}{}\r\n", kind, len));
+            Ok((None, len))
+        }
+        Set(len) => {
+            let kind = if v3 { '~' } else { '*' };
+            put_fmt(output, format_args!("{}{}\r\n", kind, len));
+            Ok((None, len))
+        }
+        SimpleError(value) | SimpleString(value) | Unknown(_, value) if newline(value) => {
+            Err(RespError::Newline)
+        }
+        SimpleError(value) => {
+            output.put_u8(b'-');
+            Ok((Some(value), 0))
+        }
+        SimpleString(value) => {
+            output.put_u8(b'+');
+            Ok((Some(value), 0))
+        }
+        Unknown(kind, value) => {
+            output.put_u8(kind);
+            Ok((Some(value), 0))
+        }
+        Verbatim(format, value) => {
+            if format.len() != 3 || format.contains(&b':') {
+                return Err(RespError::InvalidVerbatim);
+            }
+            if newline(format) {
+                return Err(RespError::Newline);
+            }
+            if v3 {
+                let len = format.len() + 1 + value.len();
+                put_fmt(output, format_args!("={}\r\n", len));
+                output.put_slice(format);
+                output.put_u8(b':');
+            } else {
+                put_fmt(output, format_args!("${}\r\n", value.len()));
+            }
+            Ok((Some(value), 0))
+        }
+    }
+}
+
+/// Format `args` at the end of `output`.
+fn put_fmt<B: BufMut>(output: &mut B, args: fmt::Arguments<'_>) {
+    output.writer().write_fmt(args).unwrap();
+}
+
+/// Write the text of a double, in the form the reader expects.
+fn write_double_text<B: BufMut>(output: &mut B, value: f64) {
+    match value {
+        f64::INFINITY => output.put_slice(b"inf"),
+        f64::NEG_INFINITY => output.put_slice(b"-inf"),
+        _ if value.is_nan() => output.put_slice(b"nan"),
+        _ => put_fmt(output, format_args!("{}", value)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{RespConfig, RespReader};
     use std::str::from_utf8;
 
     macro_rules! assert_write {
         ($f:ident ( $($arg:expr),* ), $expected:expr, $version:expr) => {{
             let mut output = Vec::new();
             let mut writer = RespWriter::new(&mut output);
-            writer.version = $version;
+            writer.set_version($version);
             writer.$f($($arg),*).await?;
             drop(writer);
             match (from_utf8(&output[..]), from_utf8($expected)) {
@@ -261,7 +854,7 @@ mod tests {
         ($f:ident ( $($arg:expr),* ), $expected:pat, $version:expr) => {{
             let mut output = Vec::new();
             let mut writer = RespWriter::new(&mut output);
-            writer.version = $version;
+            writer.set_version($version);
             let error = writer.$f($($arg),*).await.expect_err("got Ok(_)");
             drop(writer);
             assert!(matches!(error, $expected));
@@ -300,6 +893,321 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn write_null_array() -> Result<(), RespError> {
+        assert_write2!(write_null_array(), b"*-1\r\n");
+        assert_write3!(write_null_array(), b"_\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_null_string() -> Result<(), RespError> {
+        assert_write2!(write_null_string(), b"$-1\r\n");
+        assert_write3!(write_null_string(), b"_\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_null_array_element() -> Result<(), RespError> {
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.write_array(2).await?;
+        writer.write_blob_string(b"x").await?;
+        writer.write_null_array().await?;
+        drop(writer);
+        assert_eq!(&output[..], b"*2\r\n$1\r\nx\r\n*-1\r\n");
+
+        let mut reader = RespReader::new(&output[..], RespConfig::default());
+        assert_eq!(reader.value().await?, Some(resp! { ["x", nil] }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn frame_checks() -> Result<(), RespError> {
+        for version in [RespVersion::V2, RespVersion::V3] {
+            let mut output = Vec::new();
+            let mut writer = RespWriter::new(&mut output);
+            writer.set_version(version);
+            writer.set_frame_checks(true);
+            writer.write_map(2).await?;
+            writer.write_blob_string(b"a").await?;
+            writer.write_array(1).await?;
+            writer.write_integer(1).await?;
+            writer.write_blob_string(b"b").await?;
+            assert!(matches!(writer.flush().await, Err(RespError::FrameCount)));
+
+            writer.write_map(1).await?;
+            writer.write_blob_string(b"a").await?;
+            writer.write_set(1).await?;
+            writer.write_integer(1).await?;
+            writer.write_nil().await?;
+            writer.flush().await?;
+        }
+
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.write_map(2).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_value() -> Result<(), RespError> {
+        let value = resp! { [1, "x", nil, [> true], {"a" => 1.5f64}, {"b"}] };
+        assert_write2!(
+            write_value(&value),
+            b"*6\r\n:1\r\n$1\r\nx\r\n$-1\r\n*1\r\n:1\r\n*2\r\n$1\r\na\r\n+1.5\r\n*1\r\n$1\r\nb\r\n"
+        );
+        assert_write3!(
+            write_value(&value),
+            b"*6\r\n:1\r\n$1\r\nx\r\n_\r\n>1\r\n#t\r\n%1\r\n$1\r\na\r\n,1.5\r\n~1\r\n$1\r\nb\r\n"
+        );
+
+        let value = resp! { {a "ttl" => 1} };
+        assert_error2!(write_value(&value), RespError::Version);
+        assert_write3!(write_value(&value), b"|1\r\n$3\r\nttl\r\n:1\r\n");
+
+        let value = resp! { [(big "123"), (! "ERR x"), (= "txt", "abc")] };
+        assert_write3!(
+            write_value(&value),
+            b"*3\r\n(123\r\n-ERR x\r\n=7\r\ntxt:abc\r\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_value_multiline_error() -> Result<(), RespError> {
+        let input = "!10\r\nERR a\r\nb c\r\n";
+        let mut reader = RespReader::new(input.as_bytes(), RespConfig::default());
+        let value = reader.value().await?.unwrap();
+        assert_eq!(value, resp! { (! "ERR a\r\nb c") });
+
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.set_version(RespVersion::V3);
+        writer.write_value(&value).await?;
+        drop(writer);
+        assert_eq!(output, input.as_bytes());
+        let mut reader = RespReader::new(&output[..], RespConfig::default());
+        assert_eq!(reader.value().await?, Some(value.clone()));
+
+        assert_write2!(write_value(&value), b"-ERR a  b c\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_value_depth_limit() -> Result<(), RespError> {
+        let mut value = RespValue::Nil;
+        for _ in 0..2000 {
+            value = RespValue::Array(vec![value]);
+        }
+        assert_error2!(write_value(&value), RespError::DepthLimit);
+
+        let value = resp! { [[1]] };
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.set_depth_limit(2);
+        assert!(matches!(
+            writer.write_value(&value).await,
+            Err(RespError::DepthLimit)
+        ));
+        writer.set_depth_limit(3);
+        writer.write_value(&value).await?;
+
+        let value = resp! { [{"a"}] };
+        writer.set_depth_limit(2);
+        assert!(matches!(
+            writer.write_value(&value).await,
+            Err(RespError::DepthLimit)
+        ));
+
+        let value = resp! { [{"a" => 1}] };
+        assert!(matches!(
+            writer.write_value(&value).await,
+            Err(RespError::DepthLimit)
+        ));
+        writer.set_depth_limit(3);
+        writer.write_value(&value).await?;
+
+        writer.set_depth_limit(0);
+        assert!(matches!(
+            writer.write_primitive(&1.into()).await,
+            Err(RespError::DepthLimit)
+        ));
+        drop(writer);
+        assert_eq!(
+            &output[..],
+            b"*1\r\n*1\r\n:1\r\n*1\r\n*2\r\n$1\r\na\r\n:1\r\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_value_depth_limit_writes_nothing() -> Result<(), RespError> {
+        let value = resp! { [1, [2, [3, [4]]], "x"] };
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.set_frame_checks(true);
+        writer.set_depth_limit(3);
+        assert!(matches!(
+            writer.write_value(&value).await,
+            Err(RespError::DepthLimit)
+        ));
+        writer.write_integer(5).await?;
+        writer.flush().await?;
+        drop(writer);
+        assert_eq!(&output[..], b":5\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_value_round_trip() -> Result<(), RespError> {
+        let value = resp! { [1, "x", nil, [> true], {"a" => 1.5f64}, {"b"}, (= "txt", "abc")] };
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.set_version(RespVersion::V3);
+        writer.write_value(&value).await?;
+        drop(writer);
+
+        let mut reader = RespReader::new(&output[..], RespConfig::default());
+        assert_eq!(reader.value().await?, Some(value));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_nil_round_trip() -> Result<(), RespError> {
+        let value = resp! { [nil, "x"] };
+        for (version, expected) in [
+            (RespVersion::V2, &b"*2\r\n$-1\r\n$1\r\nx\r\n"[..]),
+            (RespVersion::V3, &b"*2\r\n_\r\n$1\r\nx\r\n"[..]),
+        ] {
+            let mut output = Vec::new();
+            let mut writer = RespWriter::new(&mut output);
+            writer.set_version(version);
+            writer.write_value(&value).await?;
+            writer.flush().await?;
+            assert_eq!(output, expected);
+
+            let mut reader = RespReader::new(&output[..], RespConfig::default());
+            assert_eq!(reader.value().await?, Some(value.clone()));
+        }
+        Ok(())
+    }
+
+    /// An [`AsyncWrite`] that counts calls to write.
+    #[derive(Default)]
+    struct CountingWriter {
+        output: Vec<u8>,
+        writes: usize,
+    }
+
+    impl AsyncWrite for CountingWriter {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            self.writes += 1;
+            self.output.extend_from_slice(buf);
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_write_vectored(
+            mut self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            bufs: &[IoSlice<'_>],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            self.writes += 1;
+            let mut len = 0;
+            for buf in bufs {
+                self.output.extend_from_slice(buf);
+                len += buf.len();
+            }
+            std::task::Poll::Ready(Ok(len))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            true
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn buffer_threshold() -> Result<(), RespError> {
+        let mut writer = RespWriter::new(CountingWriter::default());
+        writer.set_buffer_threshold(1024);
+        writer.write_simple_string(b"OK").await?;
+        writer.write_integer(1).await?;
+        writer.write_blob_string(b"foo").await?;
+        assert_eq!(writer.inner.writes, 0);
+        writer.flush().await?;
+        assert_eq!(writer.inner.writes, 1);
+        assert_eq!(&writer.inner.output[..], b"+OK\r\n:1\r\n$3\r\nfoo\r\n");
+
+        let mut writer = RespWriter::new(CountingWriter::default());
+        writer.set_buffer_threshold(8);
+        writer.write_simple_string(b"OK").await?;
+        assert_eq!(writer.inner.writes, 0);
+        writer.write_blob_string(b"foo").await?;
+        assert_eq!(writer.inner.writes, 1);
+        assert_eq!(&writer.inner.output[..], b"+OK\r\n$3\r\nfoo\r\n");
+
+        let mut writer = RespWriter::new(CountingWriter::default());
+        writer.write_blob_string(b"foo").await?;
+        assert_eq!(writer.inner.writes, 1);
+        writer.write_blob_string(b"bar").await?;
+        assert_eq!(writer.inner.writes, 2);
+
+        let large = vec![b'x'; DIRECT_WRITE_SIZE];
+        let mut writer = RespWriter::new(CountingWriter::default());
+        writer.set_buffer_threshold(usize::MAX);
+        writer.write_blob_string(&large).await?;
+        assert_eq!(writer.inner.writes, 1);
+        writer.flush().await?;
+        assert_eq!(writer.inner.writes, 1);
+        assert_eq!(writer.inner.output.len(), DIRECT_WRITE_SIZE + 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn vectored_write() -> Result<(), RespError> {
+        let large = vec![b'x'; DIRECT_WRITE_SIZE];
+        let mut writer = RespWriter::new(CountingWriter::default());
+        writer.write_simple_string(b"OK").await?;
+        writer.write_blob_string(&large).await?;
+        writer.write_blob_error(b"ERR").await.unwrap_err();
+        writer.set_version(RespVersion::V3);
+        writer.write_blob_error(&large).await?;
+        assert_eq!(writer.inner.writes, 3);
+
+        let mut expected = b"+OK\r\n$65536\r\n".to_vec();
+        expected.extend_from_slice(&large);
+        expected.extend_from_slice(b"\r\n!65536\r\n");
+        expected.extend_from_slice(&large);
+        expected.extend_from_slice(b"\r\n");
+        assert_eq!(writer.inner.output, expected);
+
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.write_blob_string(&large).await?;
+        drop(writer);
+        assert_eq!(output.len(), DIRECT_WRITE_SIZE + 10);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn write_array() -> Result<(), RespError> {
         assert_write2!(write_array(0), b"*0\r\n");
@@ -334,6 +1242,89 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn write_frame() -> Result<(), RespError> {
+        use crate::{RespConfig, RespReader};
+
+        let frames = [
+            RespFrame::Array(2),
+            RespFrame::Attribute(1),
+            RespFrame::Bignum("123".into()),
+            RespFrame::BlobError("ERR x".into()),
+            RespFrame::BlobString("abc".into()),
+            RespFrame::Boolean(true),
+            RespFrame::Double(1.5.into()),
+            RespFrame::Integer(-1),
+            RespFrame::Map(1),
+            RespFrame::Nil,
+            RespFrame::Push(2),
+            RespFrame::Set(3),
+            RespFrame::SimpleError("ERR".into()),
+            RespFrame::SimpleString("OK".into()),
+            RespFrame::Verbatim("txt".into(), "abc".into()),
+        ];
+
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.set_version(RespVersion::V3);
+        for frame in &frames {
+            writer.write_frame(frame).await?;
+        }
+        writer.flush().await?;
+
+        let mut reader = RespReader::new(&output[..], RespConfig::default());
+        for frame in frames {
+            assert_eq!(reader.frame().await?, Some(frame));
+        }
+        assert_eq!(reader.frame().await?, None);
+
+        assert_error2!(write_frame(&RespFrame::Attribute(1)), RespError::Version);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_map_entries() -> Result<(), RespError> {
+        let entries = || {
+            vec![
+                (resp! { "a" }, resp! { 1 }),
+                (resp! { "b" }, resp! { [nil] }),
+            ]
+        };
+        assert_write2!(
+            write_map_entries(entries()),
+            b"*4\r\n$1\r\na\r\n:1\r\n$1\r\nb\r\n*1\r\n$-1\r\n"
+        );
+        assert_write3!(
+            write_map_entries(entries()),
+            b"%2\r\n$1\r\na\r\n:1\r\n$1\r\nb\r\n*1\r\n_\r\n"
+        );
+        assert_write3!(write_map_entries(Vec::new()), b"%0\r\n");
+
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.set_frame_checks(true);
+        writer.write_map_entries(entries()).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_unknown() -> Result<(), RespError> {
+        assert_write2!(write_unknown(b'@', b"x y"), b"@x y\r\n");
+        assert_write3!(write_unknown(b'@', b""), b"@\r\n");
+        assert_error3!(write_unknown(b'@', b"x\ny"), RespError::Newline);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_error() -> Result<(), RespError> {
+        assert_write2!(write_error(b"ERR x"), b"-ERR x\r\n");
+        assert_write3!(write_error(b"ERR x"), b"-ERR x\r\n");
+        assert_write2!(write_error(b"ERR a\r\nb\nc"), b"-ERR a  b c\r\n");
+        assert_write3!(write_error(b"ERR a\r\nb\nc"), b"!10\r\nERR a\r\nb\nc\r\n");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn write_blob_string() -> Result<(), RespError> {
         assert_write2!(write_blob_string("12345".as_bytes()), b"$5\r\n12345\r\n");
@@ -354,6 +1345,45 @@ mod tests {
     async fn write_double() -> Result<(), RespError> {
         assert_write2!(write_double(1.23f64), b"+1.23\r\n");
         assert_write3!(write_double(1.23f64), b",1.23\r\n");
+        assert_write2!(write_double(f64::INFINITY), b"+inf\r\n");
+        assert_write2!(write_double(f64::NEG_INFINITY), b"+-inf\r\n");
+        assert_write2!(write_double(f64::NAN), b"+nan\r\n");
+        assert_write3!(write_double(f64::INFINITY), b",inf\r\n");
+        assert_write3!(write_double(f64::NEG_INFINITY), b",-inf\r\n");
+        assert_write3!(write_double(f64::NAN), b",nan\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_blob_doubles() -> Result<(), RespError> {
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        writer.set_blob_doubles(true);
+        writer.write_double(1.5).await?;
+        writer.write_double(f64::NEG_INFINITY).await?;
+        writer.set_version(RespVersion::V3);
+        writer.write_double(1.5).await?;
+        writer.set_version(RespVersion::V2);
+        writer.set_blob_doubles(false);
+        writer.write_double(1.5).await?;
+        writer.flush().await?;
+        assert_eq!(output, b"$3\r\n1.5\r\n$4\r\n-inf\r\n,1.5\r\n+1.5\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_double_round_trip() -> Result<(), RespError> {
+        use crate::{RespConfig, RespReader};
+
+        for value in [1.5, -0.25, f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+            let mut output = Vec::new();
+            let mut writer = RespWriter::new(&mut output);
+            writer.set_version(RespVersion::V3);
+            writer.write_double(value).await?;
+            writer.flush().await?;
+            let mut reader = RespReader::new(&output[..], RespConfig::default());
+            assert_eq!(reader.value().await?, Some(RespValue::Double(value.into())));
+        }
         Ok(())
     }
 
@@ -437,6 +1467,183 @@ mod tests {
             write_verbatim("txt".as_bytes(), "1234567890".as_bytes()),
             b"=14\r\ntxt:1234567890\r\n"
         );
+        assert_error2!(
+            write_verbatim("tx".as_bytes(), "abc".as_bytes()),
+            RespError::InvalidVerbatim
+        );
+        assert_error3!(
+            write_verbatim("tx".as_bytes(), "abc".as_bytes()),
+            RespError::InvalidVerbatim
+        );
+        assert_error2!(
+            write_verbatim("text".as_bytes(), "abc".as_bytes()),
+            RespError::InvalidVerbatim
+        );
+        assert_error3!(
+            write_verbatim("text".as_bytes(), "abc".as_bytes()),
+            RespError::InvalidVerbatim
+        );
+        assert_error2!(
+            write_verbatim("t:t".as_bytes(), "abc".as_bytes()),
+            RespError::InvalidVerbatim
+        );
+        assert_error3!(
+            write_verbatim("t:t".as_bytes(), "abc".as_bytes()),
+            RespError::InvalidVerbatim
+        );
+        assert_error2!(
+            write_verbatim("t\nt".as_bytes(), "abc".as_bytes()),
+            RespError::Newline
+        );
+        assert_error3!(
+            write_verbatim("t\rt".as_bytes(), "abc".as_bytes()),
+            RespError::Newline
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn array_scope() -> Result<(), RespError> {
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        let mut array = writer.array_scope(4).await?;
+        array.write_blob_string(b"a").await?;
+        array.write_integer(1).await?;
+        array.write_value(&resp! { [nil] }).await?;
+        array.element()?.write_map(0).await?;
+        assert!(matches!(
+            array.write_nil().await,
+            Err(RespError::FrameCount)
+        ));
+        array.finish()?;
+        drop(writer);
+        assert_eq!(&output[..], b"*4\r\n$1\r\na\r\n:1\r\n*1\r\n$-1\r\n*0\r\n");
+
+        let mut output = Vec::new();
+        let mut writer = RespWriter::new(&mut output);
+        let mut array = writer.array_scope(2).await?;
+        array.write_integer(1).await?;
+        assert!(matches!(array.finish(), Err(RespError::FrameCount)));
+        Ok(())
+    }
+
+    /// An [`AsyncWrite`] that fails once it has accepted `capacity` bytes.
+    struct FailingWriter {
+        capacity: usize,
+    }
+
+    impl AsyncWrite for FailingWriter {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            if buf.len() > self.capacity {
+                return std::task::Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into()));
+            }
+            self.capacity -= buf.len();
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn array_scope_failed_write() -> Result<(), RespError> {
+        // Room for the header and the first element only.
+        let mut writer = RespWriter::new(FailingWriter { capacity: 8 });
+        let mut array = writer.array_scope(2).await?;
+        array.write_integer(1).await?;
+        assert!(matches!(
+            array.write_integer(2).await,
+            Err(RespError::IO(_))
+        ));
+        // The failed element isn't counted.
+        assert!(matches!(array.finish(), Err(RespError::FrameCount)));
+
+        // Dropping a scope after a failed write doesn't panic.
+        let mut writer = RespWriter::new(FailingWriter { capacity: 4 });
+        let mut array = writer.array_scope(2).await?;
+        assert!(array.write_nil().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn into_inner() -> Result<(), RespError> {
+        let mut writer = RespWriter::new(Vec::new());
+        writer.write_integer(1).await?;
+        writer.flush().await?;
+        assert_eq!(writer.into_inner(), b":1\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reset() -> Result<(), RespError> {
+        let mut writer = RespWriter::new(Vec::new());
+        writer.set_version(RespVersion::V3);
+        writer.set_buffer_threshold(1024);
+        writer.set_frame_checks(true);
+        writer.write_array(2).await?;
+        writer.write_nil().await?;
+        let capacity = writer.buffer.capacity();
+
+        writer.reset(Vec::new());
+        assert_eq!(writer.version(), RespVersion::V2);
+        assert!(writer.buffer.is_empty());
+        assert_eq!(writer.buffer.capacity(), capacity);
+        writer.write_nil().await?;
+        writer.flush().await?;
+        assert_eq!(writer.into_inner(), b"$-1\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn version() -> Result<(), RespError> {
+        let mut writer = RespWriter::new(Vec::new());
+        assert_eq!(writer.version(), RespVersion::V2);
+        writer.write_nil().await?;
+        writer.set_version(RespVersion::V3);
+        assert_eq!(writer.version(), RespVersion::V3);
+        writer.write_nil().await?;
+        assert_eq!(writer.into_inner(), b"$-1\r\n_\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_values() -> Result<(), RespError> {
+        let values = [resp! { (+ "OK") }, resp! { ["a", 1] }, RespValue::Nil];
+        let mut writer = RespWriter::new(CountingWriter::default());
+        writer.write_values(&values).await?;
+        let inner = writer.into_inner();
+        assert_eq!(inner.writes, 1);
+
+        let mut reader = RespReader::new(&inner.output[..], RespConfig::default());
+        for value in values {
+            assert_eq!(reader.value().await?, Some(value));
+        }
+        assert_eq!(reader.value().await?, None);
+
+        // The buffer threshold is restored, even after an error.
+        let mut writer = RespWriter::new(CountingWriter::default());
+        writer.set_depth_limit(1);
+        let values = vec![RespValue::Integer(1), resp! { [1] }];
+        assert!(matches!(
+            writer.write_values(values).await,
+            Err(RespError::DepthLimit)
+        ));
+        assert_eq!(writer.buffer_threshold, 0);
         Ok(())
     }
 }